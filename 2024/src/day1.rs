@@ -1,5 +1,7 @@
 use std::vec;
 
+use aoc_common::ints::ints_n;
+
 use crate::utils::read_lines;
 
 #[allow(dead_code)]
@@ -9,9 +11,9 @@ pub fn run() {
 
     if let Ok(lines) = read_lines("1") {
         for line in lines.map_while(Result::ok) {
-            let mut parts = line.split_whitespace();
-            list1.push(parts.next().expect("whoa whoa wee whoa").parse().unwrap());
-            list2.push(parts.next().expect("whoa whoa we whoa").parse().unwrap());
+            let [left, right] = ints_n(&line);
+            list1.push(left);
+            list2.push(right);
         }
     } else {
         panic!("orrr noorrrr");
@@ -1,4 +1,6 @@
-use std::{collections::HashMap, fmt::Display, vec::Vec};
+use std::{fmt::Display, iter::Sum, ops::Add, vec::Vec};
+
+use aoc_common::{bigint::BigInt, digits, memo::Memo};
 
 struct Arrangement {
     stones: Vec<u64>,
@@ -27,29 +29,21 @@ impl Display for Arrangement {
     }
 }
 
-fn rec_blink(blink_count: usize, stone: u64, cache: &mut HashMap<(usize, u64), u64>) -> u64 {
-    let key = (blink_count, stone);
-    if cache.contains_key(&key) {
-        return cache[&key];
-    }
+fn rec_blink<T>(blink: &mut dyn FnMut((usize, u64)) -> T, (blink_count, stone): (usize, u64)) -> T
+where
+    T: From<u8> + Add<Output = T>,
+{
     if blink_count == 0 {
-        return 1;
+        return T::from(1);
     }
 
-    let count = if stone == 0 {
-        rec_blink(blink_count - 1, 1, cache)
-    } else if stone.to_string().len() % 2 == 0 {
-        let num = stone.to_string();
-        let (first, second) = num.split_at(num.len() / 2);
-        rec_blink(blink_count - 1, first.parse().unwrap(), cache)
-            + rec_blink(blink_count - 1, second.parse().unwrap(), cache)
+    if stone == 0 {
+        blink((blink_count - 1, 1))
+    } else if let Some((first, second)) = digits::split_half(stone) {
+        blink((blink_count - 1, first)) + blink((blink_count - 1, second))
     } else {
-        rec_blink(blink_count - 1, stone * 2024, cache)
-    };
-
-    cache.insert(key, count);
-
-    count
+        blink((blink_count - 1, stone * 2024))
+    }
 }
 
 impl Arrangement {
@@ -58,11 +52,9 @@ impl Arrangement {
         self.stones.iter().for_each(|s| {
             if *s == 0 {
                 new_stones.push(1);
-            } else if s.to_string().len() % 2 == 0 {
-                let num = s.to_string();
-                let (first, second) = num.split_at(num.len() / 2);
-                new_stones.push(first.parse().unwrap());
-                new_stones.push(second.parse().unwrap());
+            } else if let Some((first, second)) = digits::split_half(*s) {
+                new_stones.push(first);
+                new_stones.push(second);
             } else {
                 new_stones.push(s * 2024);
             }
@@ -71,14 +63,24 @@ impl Arrangement {
         Arrangement { stones: new_stones }
     }
 
+    fn count_after<T>(&self, blink_count: usize) -> T
+    where
+        T: From<u8> + Add<Output = T> + Sum + Clone,
+    {
+        let mut blink = Memo::new(rec_blink::<T>);
+        self.stones
+            .iter()
+            .map(|stone| blink.get((blink_count, *stone)))
+            .sum()
+    }
+
     fn blink_and_count(self, blink_count: usize) -> u64 {
-        let mut stone_count: u64 = 0;
-        let mut cache: HashMap<(usize, u64), u64> = HashMap::new();
-        self.stones.iter().for_each(|stone| {
-            stone_count += rec_blink(blink_count, *stone, &mut cache);
-        });
+        self.count_after(blink_count)
+    }
 
-        stone_count
+    /// For blink counts where the stone count no longer fits in a `u64`.
+    fn blink_and_count_big(self, blink_count: usize) -> BigInt {
+        self.count_after(blink_count)
     }
 }
 
@@ -89,7 +91,7 @@ pub fn run() {
     println!("stones: {stone_count}");
 
     let arrangement = Arrangement::from(input);
-    let giga_count = arrangement.blink_and_count(75);
+    let giga_count = arrangement.blink_and_count_big(75);
     // let giga_blinked = (0..75).fold(Arrangement::from(input), |acc, _| acc.blink());
     // let giga_count = giga_blinked.stones.len();
     println!("giga stones: {giga_count}");
@@ -112,4 +114,21 @@ mod tests {
         // let stone_count = blinked.stones.len();
         // assert_eq!(55312, stone_count);
     }
+
+    #[test]
+    fn big_counts() {
+        let input = "125 17";
+
+        let small = Arrangement::from(input).blink_and_count_big(25);
+        assert_eq!(BigInt::from(55312), small);
+
+        let same = Arrangement::from(input).blink_and_count(75);
+        assert_eq!(
+            BigInt::from(same),
+            Arrangement::from(input).blink_and_count_big(75)
+        );
+
+        let huge = Arrangement::from(input).blink_and_count_big(500);
+        assert!(huge > BigInt::from(u128::MAX));
+    }
 }
@@ -1,8 +1,16 @@
-use std::num::TryFromIntError;
+use aoc_common::{
+    answer::Answer, bigint::BigInt, input::sections, parallel::ParallelSlice, FromLine,
+};
+
+/// How much further away the prizes really are in part 2.
+const FAR: u64 = 10000000000000;
 
 use crate::utils::read_lines;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, FromLine)]
+#[pattern(
+    "Button A: X+{button_a.0}, Y+{button_a.1}\nButton B: X+{button_b.0}, Y+{button_b.1}\nPrize: X={prize.0}, Y={prize.1}"
+)]
 struct Machine {
     button_a: (usize, usize),
     button_b: (usize, usize),
@@ -39,90 +47,36 @@ impl Machine {
         min_cost
     }
 
-    fn unique(&self) -> bool {
-        self.button_a.0 * self.button_a.1 != self.button_b.0 * self.button_b.1
-    }
-
-    // a1*b2 - a2*b1
-    fn cross_mult_1(&self) -> i128 {
-        (self.button_a.0 * self.button_b.1) as i128 - (self.button_a.1 * self.button_b.0) as i128
-    }
-
-    // b1*c2 - b2*c1
-    fn cross_mult_2(&self) -> i128 {
-        (self.button_a.1 * self.prize.1) as i128 - (self.button_b.1 * self.prize.0) as i128
-    }
-
-    // a1*c2 - a2*c1
-    fn cross_mult_3(&self) -> i128 {
-        (self.button_a.0 * self.prize.1) as i128 - (self.button_b.0 * self.prize.1) as i128
-    }
-
-    fn inconsistent(&self) -> bool {
-        if self.cross_mult_1() != 0 {
-            return false;
-        }
-
-        if self.cross_mult_2() == 0 {
-            return false;
-        }
-
-        if self.cross_mult_3() == 0 {
-            return false;
-        }
-
-        true
-    }
-
-    fn infinite_solutions(&self) -> bool {
-        if self.cross_mult_1() != 0 {
-            return false;
-        }
-
-        if self.cross_mult_2() != 0 {
-            return false;
-        }
-
-        if self.cross_mult_3() != 0 {
-            return false;
-        }
-
-        true
-    }
-
-    fn solve_d2(&self) -> Option<usize> {
-        if !self.unique() || self.inconsistent() || self.infinite_solutions() {
-            println!(
-                "self: {:?}, unique: {}, inconsistent: {}, infinite: {}",
-                self,
-                self.unique(),
-                self.inconsistent(),
-                self.infinite_solutions()
-            );
-            return None;
-        }
-
-        let denominator = (self.button_b.1 as i128 * self.button_a.0 as i128)
-            - (self.button_b.0 as i128 * self.button_a.1 as i128);
+    /// The cheapest way to win once the prize has moved [`FAR`] further along both axes, worked
+    /// out in [`BigInt`] so the offset can't overflow anything. Two buttons and two axes only
+    /// allow one pair of press counts, and it has to be whole and not negative.
+    fn solve_d2(&self) -> Option<BigInt> {
+        let [ax, ay, bx, by] = [
+            self.button_a.0,
+            self.button_a.1,
+            self.button_b.0,
+            self.button_b.1,
+        ]
+        .map(BigInt::from);
+        let far = BigInt::from(FAR);
+        let px = BigInt::from(self.prize.0) + &far;
+        let py = BigInt::from(self.prize.1) + &far;
 
-        if denominator == 0 {
+        let determinant = &ax * &by - &ay * &bx;
+        if determinant.is_zero() {
             return None;
         }
-
-        let a_numerator: i128 = (-(self.button_b.0 as i128) * self.prize.1 as i128)
-            - (-(self.prize.0 as i128) * self.button_b.1 as i128);
-        let b_numerator: i128 = (-(self.prize.0 as i128) * self.button_a.1 as i128)
-            - (-(self.prize.1 as i128) * self.button_a.0 as i128);
-
-        if a_numerator % denominator != 0 || b_numerator % denominator != 0 {
+        let (a_presses, a_rest) = (&px * &by - &py * &bx).div_rem(&determinant);
+        let (b_presses, b_rest) = (&ax * &py - &ay * &px).div_rem(&determinant);
+        if !a_rest.is_zero()
+            || !b_rest.is_zero()
+            || a_presses.is_negative()
+            || b_presses.is_negative()
+        {
             return None;
         }
-        let a_presses: i128 = a_numerator / denominator;
-        let b_presses: i128 = b_numerator / denominator;
-
-        let cost: Result<usize, TryFromIntError> = (a_presses * 3 + b_presses).try_into();
 
-        cost.ok()
+        Some(a_presses * BigInt::from(3) + b_presses)
     }
 }
 
@@ -134,61 +88,23 @@ struct Arcade {
 impl Arcade {
     fn solve(&self) -> usize {
         self.machines
-            .iter()
-            .filter_map(|machine| machine.solve())
-            .sum()
+            .par_sum(|machine| machine.solve().unwrap_or(0))
     }
 
-    fn solve_d2(&self) -> usize {
-        self.machines
-            .iter()
-            .filter_map(|machine| machine.solve_d2())
-            .sum()
-    }
-}
-
-fn extract_pair(line: String, num_prefix: &str) -> (usize, usize) {
-    let numbers: Vec<usize> = line
-        .split_once(":")
-        .unwrap()
-        .1
-        .split(",")
-        .map(|x| x.trim())
-        .map(|x| x.split_once(num_prefix).unwrap().1)
-        .map(|x| x.parse::<usize>().unwrap())
-        .collect();
-
-    match &numbers[..] {
-        &[first, second, ..] => (first, second),
-        _ => unreachable!(),
+    fn solve_d2(&self) -> Answer {
+        Answer::from(
+            self.machines
+                .par_sum(|machine| machine.solve_d2().unwrap_or_default()),
+        )
     }
 }
 
 impl From<Vec<String>> for Arcade {
     fn from(lines: Vec<String>) -> Self {
-        let mut machines: Vec<Machine> = vec![];
-
-        let mut line_iter = lines.into_iter();
-        loop {
-            let b_a_line = line_iter.next();
-            if b_a_line.is_none() {
-                break;
-            }
-            let button_a = extract_pair(b_a_line.unwrap(), "+");
-            let button_b = extract_pair(line_iter.next().unwrap(), "+");
-            let prize = extract_pair(line_iter.next().unwrap(), "=");
-            let d2_prize = (prize.0 + 10000000000000, prize.1 + 10000000000000);
-
-            machines.push(Machine {
-                button_a,
-                button_b,
-                prize: d2_prize,
-            });
+        let machines = sections(lines.iter().map(String::as_str))
+            .map(|section| section.lines.join("\n").parse().unwrap())
+            .collect();
 
-            if line_iter.next().is_none() {
-                break;
-            }
-        }
         Arcade { machines }
     }
 }
@@ -201,9 +117,8 @@ pub fn run() {
         panic!("ahhhhh");
     };
 
-    // let solution = arcade.solve();
-    let solution = arcade.solve_d2();
-    println!("solution: {solution}");
+    println!("solution: {}", arcade.solve());
+    println!("far away solution: {}", arcade.solve_d2());
 }
 
 #[cfg(test)]
@@ -213,12 +128,12 @@ mod tests {
     #[test]
     fn day13() {
         assert_eq!(
-            (94, 34),
-            extract_pair("Button A: X+94, Y+34".to_string(), "+")
-        );
-        assert_eq!(
-            (8400, 5400),
-            extract_pair("Prize: X=8400, Y=5400".to_string(), "=")
+            Ok(Machine {
+                button_a: (94, 34),
+                button_b: (22, 67),
+                prize: (8400, 5400)
+            }),
+            "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400".parse()
         );
         let lines: Vec<String> = vec![
             "Button A: X+94, Y+34",
@@ -241,7 +156,7 @@ mod tests {
         .map(|x| x.to_string())
         .collect();
         let arcade = Arcade::from(lines);
-        // assert_eq!(480, arcade.solve());
-        assert_eq!(1545093008499, arcade.solve_d2());
+        assert_eq!(480, arcade.solve());
+        assert_eq!(Answer::from(875318608908_u64), arcade.solve_d2());
     }
 }
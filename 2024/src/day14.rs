@@ -1,8 +1,16 @@
-use std::{collections::HashMap, ops::Range};
+use std::ops::Range;
+
+use aoc_common::{
+    grid::{self, Pos, Torus},
+    prefix::PrefixSum2D,
+    simulation::{Runner, Simulation},
+    FromLine,
+};
 
 use crate::utils::read_lines;
 
-#[derive(Debug)]
+#[derive(Debug, FromLine)]
+#[pattern("p={position.0},{position.1} v={velocity.0},{velocity.1}")]
 struct Robot {
     velocity: (i32, i32),
     position: (usize, usize),
@@ -11,84 +19,80 @@ struct Robot {
 #[derive(Debug)]
 struct Grid {
     robots: Vec<Robot>,
-    dimensions: (usize, usize),
+    /// How many robots are on each tile.
+    floor: Torus<u32>,
 }
 
 impl Grid {
     fn new(dimensions: (usize, usize), lines: Vec<String>) -> Self {
         let mut robots: Vec<Robot> = Vec::new();
-        lines
-            .iter()
-            .for_each(|l| robots.push(Robot::from(l.clone())));
+        lines.iter().for_each(|l| robots.push(l.parse().unwrap()));
+
+        let mut floor = Torus::new(grid::Grid::new(dimensions.0, dimensions.1, 0));
+        robots.iter().for_each(|r| floor[r.tile()] += 1);
 
-        Grid { robots, dimensions }
+        Grid { robots, floor }
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.floor.width(), self.floor.height())
     }
 
     fn step(&mut self, times: i32) {
-        self.robots
-            .iter_mut()
-            .for_each(|r| r.step(self.dimensions, times));
+        for robot in self.robots.iter_mut() {
+            self.floor[robot.tile()] -= 1;
+            robot.step(&self.floor, times);
+            self.floor[robot.tile()] += 1;
+        }
     }
 
-    fn quadrant_score(&self, ranges: (Range<usize>, Range<usize>)) -> usize {
-        let map = self.count_map(); // Optimization? i hardly know her
-        let mut sum: usize = 0;
-        ranges.0.for_each(|x| {
-            ranges.1.clone().for_each(|y| {
-                if let Some(count) = map.get(&(x, y)) {
-                    sum += *count
-                }
-            })
-        });
+    /// `ranges` are `(x, y)`, and the floor is indexed `(row, col)`, hence the swap.
+    fn quadrant_score(counts: &PrefixSum2D, ranges: (Range<usize>, Range<usize>)) -> usize {
+        counts.sum(ranges.1, ranges.0) as usize
+    }
 
-        sum
+    /// How many robots are in any rectangle.
+    fn region_counts(&self) -> PrefixSum2D {
+        PrefixSum2D::new(self.floor.grid())
     }
 
     fn safety_factor(&self) -> usize {
+        let dimensions = self.dimensions();
         let q1 = (
-            (0..self.dimensions.0.checked_div(2).unwrap()),
-            (0..self.dimensions.1.checked_div(2).unwrap()),
+            (0..dimensions.0.checked_div(2).unwrap()),
+            (0..dimensions.1.checked_div(2).unwrap()),
         );
         let q2 = (
-            (self.dimensions.0.div_ceil(2)..self.dimensions.0),
-            (0..self.dimensions.1.checked_div(2).unwrap()),
+            (dimensions.0.div_ceil(2)..dimensions.0),
+            (0..dimensions.1.checked_div(2).unwrap()),
         );
         let q3 = (
-            (0..self.dimensions.0.checked_div(2).unwrap()),
-            (self.dimensions.1.div_ceil(2)..self.dimensions.1),
+            (0..dimensions.0.checked_div(2).unwrap()),
+            (dimensions.1.div_ceil(2)..dimensions.1),
         );
         let q4 = (
-            (self.dimensions.0.div_ceil(2)..self.dimensions.0),
-            (self.dimensions.1.div_ceil(2)..self.dimensions.1),
+            (dimensions.0.div_ceil(2)..dimensions.0),
+            (dimensions.1.div_ceil(2)..dimensions.1),
         );
 
-        let q1_sum = self.quadrant_score(q1);
-        let q2_sum = self.quadrant_score(q2);
-        let q3_sum = self.quadrant_score(q3);
-        let q4_sum = self.quadrant_score(q4);
+        let counts = self.region_counts();
+        let q1_sum = Self::quadrant_score(&counts, q1);
+        let q2_sum = Self::quadrant_score(&counts, q2);
+        let q3_sum = Self::quadrant_score(&counts, q3);
+        let q4_sum = Self::quadrant_score(&counts, q4);
 
         println!("sums: {q1_sum}, {q2_sum}, {q3_sum}, {q4_sum}");
 
         q1_sum * q2_sum * q3_sum * q4_sum
     }
 
-    fn count_map(&self) -> HashMap<(usize, usize), usize> {
-        let mut map: HashMap<(usize, usize), usize> = HashMap::new();
-        self.robots.iter().for_each(|r| {
-            map.entry(r.position).and_modify(|x| *x += 1).or_insert(1);
-        });
-
-        map
-    }
-
     fn print(&self) {
-        let map = self.count_map();
         let longest_block = self.longest_block();
 
         println!("printing grid, longest block: {longest_block}");
-        (0..self.dimensions.1).for_each(|j| {
-            (0..self.dimensions.0).for_each(|i| {
-                if let Some(count) = map.get(&(i, j)) {
+        self.floor.grid().rows().for_each(|row| {
+            row.iter().for_each(|count| {
+                if *count > 0 {
                     print!("{count}");
                 } else {
                     print!(".");
@@ -99,14 +103,13 @@ impl Grid {
     }
 
     fn longest_block(&self) -> usize {
-        let map = self.count_map();
         let mut max_length: usize = 0;
         let mut length: usize = 0;
         let mut block: bool = false;
 
-        (0..self.dimensions.1).for_each(|j| {
-            (0..self.dimensions.0).for_each(|i| {
-                if map.contains_key(&(i, j)) {
+        self.floor.grid().rows().for_each(|row| {
+            row.iter().for_each(|count| {
+                if *count > 0 {
                     length += 1;
                     block = true;
                 } else {
@@ -123,43 +126,32 @@ impl Grid {
     }
 }
 
-impl Robot {
-    fn step(&mut self, grid_size: (usize, usize), times: i32) {
-        let move_x = self.velocity.0 * times;
-        let move_y = self.velocity.1 * times;
-        let pre_x = self.position.0 as i32 + move_x;
-        let pre_y = self.position.1 as i32 + move_y;
-        let mod_x = pre_x % grid_size.0 as i32;
-        let mod_y = pre_y % grid_size.1 as i32;
-        let fix_x: usize = if mod_x < 0 {
-            grid_size.0 - mod_x.unsigned_abs() as usize
-        } else {
-            mod_x as usize
-        };
-        let fix_y: usize = if mod_y < 0 {
-            grid_size.1 - mod_y.unsigned_abs() as usize
-        } else {
-            mod_y as usize
-        };
-
-        self.position = (fix_x, fix_y);
+impl Simulation for Grid {
+    type State = Vec<(usize, usize)>;
+
+    fn step(&mut self) {
+        self.step(1);
+    }
+
+    fn state(&self) -> Self::State {
+        self.robots.iter().map(|r| r.position).collect()
     }
 }
 
-impl From<String> for Robot {
-    fn from(value: String) -> Self {
-        let (pos, vel) = value.split_once(" ").unwrap();
+impl Robot {
+    /// Where the robot is on the floor, as `(row, col)`.
+    fn tile(&self) -> Pos {
+        (self.position.1 as isize, self.position.0 as isize)
+    }
 
-        let position: (usize, usize) = pos[2..]
-            .split_once(",")
-            .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-            .unwrap();
-        let velocity: (i32, i32) = vel[2..]
-            .split_once(",")
-            .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-            .unwrap();
+    fn step(&mut self, floor: &Torus<u32>, times: i32) {
+        let (row, col) = self.tile();
+        let (row, col) = floor.wrap((
+            row + (self.velocity.1 * times) as isize,
+            col + (self.velocity.0 * times) as isize,
+        ));
 
-        Robot { position, velocity }
+        self.position = (col, row);
     }
 }
 
@@ -174,26 +166,27 @@ pub fn run() {
     // grid.step(100);
     // let safety_factor = grid.safety_factor();
     // println!("safety factor for part 1: {safety_factor}");
-    let mut step_count = 0;
-
-    loop {
-        grid.step(1);
-        step_count += 1;
-        let longest = grid.longest_block();
-        if longest > 15 {
-            grid.print();
-            print!("steps: {step_count}");
-        }
-    }
+
+    // every robot is back where it started after width * height seconds, so the tree has to
+    // show up before then
+    let (width, height) = grid.dimensions();
+    let outcome = Runner::new()
+        .limit(width * height)
+        .until(|grid: &Grid, _| grid.longest_block() > 15)
+        .run(&mut grid);
+    grid.print();
+    println!("steps: {} ({:?})", outcome.steps, outcome.stop);
 }
 
 #[cfg(test)]
 mod tests {
+    use aoc_common::{cycle::Cycle, simulation::Stop};
+
     use super::*;
 
     #[test]
     fn day14() {
-        let robot = Robot::from("p=0,4 v=3,-3".to_string());
+        let robot: Robot = "p=0,4 v=3,-3".parse().unwrap();
         assert_eq!((0, 4), robot.position);
         assert_eq!((3, -3), robot.velocity);
 
@@ -226,6 +219,15 @@ mod tests {
         grid.print();
         grid.step(100);
         grid.print();
-        println!("score: {}", grid.safety_factor());
+        assert_eq!(12, grid.safety_factor());
+
+        let outcome = Runner::new().detect_repeats().run(&mut grid);
+        assert_eq!(
+            Stop::Repeat(Cycle {
+                start: 0,
+                period: 77
+            }),
+            outcome.stop
+        );
     }
 }
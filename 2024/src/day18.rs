@@ -1,20 +1,66 @@
-use std::collections::HashSet;
+use aoc_common::{
+    bits::BitGrid,
+    grid::{Cells, Sparse},
+    ints::ints_n,
+};
 
-struct Grid {
+/// Works on any grid of bools. A [`BitGrid`] needs to be big enough up front, a [`Sparse`] one
+/// doesn't care.
+struct Grid<C> {
     size: u16,
-    corrupt: HashSet<(u16, u16)>,
+    corrupt: C,
 }
 
-impl Grid {
-    fn new(size: u16, corrupt_lines: Vec<String>) -> Grid {
-        let corrupt: HashSet<(u16, u16)> = corrupt_lines
-            .iter()
-            .map(|l| l.split_once(",").unwrap())
-            .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-            .collect();
+impl Grid<BitGrid> {
+    fn packed(size: u16, corrupt_lines: &[String]) -> Self {
+        // coordinates run from 0 to size inclusive
+        let side = size as usize + 1;
+        Grid::new(size, BitGrid::new(side, side), corrupt_lines)
+    }
+}
+
+impl Grid<Sparse<bool>> {
+    fn sparse(size: u16, corrupt_lines: &[String]) -> Self {
+        Grid::new(size, Sparse::new(false), corrupt_lines)
+    }
+}
+
+impl<C: Cells<Cell = bool>> Grid<C> {
+    fn new(size: u16, mut corrupt: C, corrupt_lines: &[String]) -> Self {
+        for line in corrupt_lines {
+            let [x, y] = ints_n::<2, isize>(line);
+            corrupt.set_cell((y, x), true);
+        }
 
         Grid { size, corrupt }
     }
+
+    fn is_corrupt(&self, x: u16, y: u16) -> bool {
+        self.corrupt.cell((y as isize, x as isize)) == Some(true)
+    }
 }
 
 pub fn run() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage() {
+        let lines: Vec<String> = ["5,4", "4,2", "4,5", "3,0", "2,1", "6,3", "2,4", "1,5"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+
+        let packed = Grid::packed(6, &lines);
+        let sparse = Grid::sparse(6, &lines);
+        assert!(sparse.is_corrupt(2, 1));
+        assert!(!sparse.is_corrupt(1, 2));
+        for y in 0..=packed.size {
+            for x in 0..=packed.size {
+                assert_eq!(packed.is_corrupt(x, y), sparse.is_corrupt(x, y));
+            }
+        }
+    }
+}
@@ -1,14 +1,12 @@
+use aoc_common::ints::ints;
+
 use crate::utils::read_lines;
 
 pub fn run() {
     let mut reports: Vec<Vec<u16>> = vec![];
     if let Ok(lines) = read_lines("2") {
         for line in lines.map_while(Result::ok) {
-            reports.push(
-                line.split_whitespace()
-                    .filter_map(|x| x.parse().ok())
-                    .collect(),
-            );
+            reports.push(ints(&line).collect());
         }
     }
     println!("safe reports: {}", count_safe(&reports, false));
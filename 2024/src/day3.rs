@@ -1,59 +1,108 @@
 use crate::utils::read_lines;
-use regex::Regex;
+use aoc_common::{
+    checked::Context,
+    parse::{from_fn, take_while, Failure, PResult, Parser},
+    vm::{Flow, InstructionSet, Machine, Program, Vm},
+};
 
-pub fn run() {
-    if let Ok(lines) = read_lines("3") {
-        let mut sum: i32 = 0;
-        let mut sum_p2: i32 = 0;
-        let mut enabled = true;
-        for line in lines.map_while(Result::ok) {
-            sum += do_math(line.clone());
-            let (sum, still_enabled) = do_conditional_math(line, enabled);
-            sum_p2 += sum;
-            enabled = still_enabled;
-        }
-        println!("total: {sum}");
-        println!("conditional total: {sum_p2}");
-    }
+const DAY: Context = Context::day(3);
+
+/// Index into the registers.
+const TOTAL_REG: usize = 0;
+/// Index into the flags.
+const ENABLED_FLAG: usize = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    Mul(i64, i64),
+    Do,
+    Dont,
 }
 
-fn do_math(input: String) -> i32 {
-    let expr = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
+/// The corrupted memory. Part 1 only multiplies; part 2 also listens to `do()` and `don't()`.
+struct Memory {
+    conditional: bool,
+}
 
-    expr.captures_iter(input.as_str())
-        .map(|c| {
-            let (_, [first, second]) = c.extract();
+impl Memory {
+    fn part(&self) -> Context {
+        DAY.part(if self.conditional { 2 } else { 1 })
+    }
+}
 
-            first.parse::<i32>().unwrap() * second.parse::<i32>().unwrap()
-        })
-        .sum()
+/// One to three digits, and nothing longer.
+fn operand<'a>() -> impl Parser<'a, Output = i64> {
+    from_fn(|input: &'a str| {
+        let (digits, rest) = take_while(|c: char| c.is_ascii_digit()).parse_partial(input)?;
+        match digits.len() {
+            1..=3 => Ok((digits.parse().unwrap(), rest)),
+            _ => Err(Failure {
+                rest: input,
+                expected: "1 to 3 digits".to_string(),
+            }),
+        }
+    })
 }
 
-fn do_conditional_math(input: String, mut starting_state: bool) -> (i32, bool) {
-    let expr = Regex::new(r"mul\(\d{1,3},\d{1,3}\)|don\'t\(\)|do\(\)").unwrap();
-    let mul_expr = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
-    let mut total: i32 = 0;
+impl InstructionSet for Memory {
+    type Instruction = Instruction;
 
-    expr.find_iter(input.as_str())
-        .for_each(|m| match m.as_str() {
-            "don't()" => {
-                starting_state = false;
-            }
-            "do()" => {
-                starting_state = true;
-            }
-            _ => {
-                if starting_state {
-                    let (_, [first, second]) = mul_expr.captures(m.as_str()).unwrap().extract();
-                    let a = first.parse::<i32>().unwrap();
-                    let b = second.parse::<i32>().unwrap();
-                    // println!("a={a}, b={b}");
-                    total += a * b;
-                }
+    fn machine(&self) -> Machine {
+        let mut machine = Machine::new(1, 1);
+        machine.flags[ENABLED_FLAG] = true;
+        machine
+    }
+
+    fn parse<'a>(&self, text: &'a str) -> PResult<'a, Instruction> {
+        ("mul(", operand(), ",", operand(), ")")
+            .map(|(_, a, _, b, _)| Instruction::Mul(a, b))
+            .or("do()".map(|_| Instruction::Do))
+            .or("don't()".map(|_| Instruction::Dont))
+            .parse_partial(text)
+    }
+
+    fn could_start(&self, c: char) -> bool {
+        matches!(c, 'm' | 'd')
+    }
+
+    fn execute(&self, instruction: &Instruction, machine: &mut Machine) -> Flow {
+        match *instruction {
+            Instruction::Mul(a, b) if machine.flags[ENABLED_FLAG] || !self.conditional => {
+                let product = self.part().wrap(a) * b;
+                machine.registers[TOTAL_REG] = (product + machine.registers[TOTAL_REG]).get();
             }
-        });
+            Instruction::Mul(..) => {}
+            Instruction::Do => machine.flags[ENABLED_FLAG] = true,
+            Instruction::Dont => machine.flags[ENABLED_FLAG] = false,
+        }
+        Flow::Next
+    }
+}
 
-    (total, starting_state)
+pub fn run() {
+    if let Ok(lines) = read_lines("3") {
+        // the enabled flag carries over from one line to the next, and so does the vm
+        let mut plain = Vm::new(Memory { conditional: false });
+        let mut conditional = Vm::new(Memory { conditional: true });
+        for line in lines.map_while(Result::ok) {
+            let program = Program::scan(plain.set(), &line);
+            plain.run(&program);
+            conditional.run(&program);
+        }
+        println!("total: {}", plain.machine().registers[TOTAL_REG]);
+        println!(
+            "conditional total: {}",
+            conditional.machine().registers[TOTAL_REG]
+        );
+    }
+}
+
+fn do_math(input: &str, conditional: bool) -> i64 {
+    let memory = Memory { conditional };
+    let program = Program::scan(&memory, input);
+    let mut vm = Vm::new(memory);
+    vm.run(&program);
+    vm.machine().registers[TOTAL_REG]
 }
 
 #[cfg(test)]
@@ -64,14 +113,33 @@ mod tests {
     fn sample() {
         let example = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
 
-        let result = do_math(example.to_string());
+        let result = do_math(example, false);
 
         assert_eq!(result, 161);
 
         let p2_example =
             "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))don't()don't()mul(8,5)mul(1,2)";
 
-        let (p2_result, _) = do_conditional_math(p2_example.to_string(), true);
+        let p2_result = do_math(p2_example, true);
         assert_eq!(p2_result, 48);
+
+        let memory = Memory { conditional: true };
+        let program = Program::scan(&memory, p2_example);
+        assert_eq!((1, Instruction::Mul(2, 4)), program.instructions[0]);
+        assert_eq!(10, program.len());
+        let mut vm = Vm::new(memory).traced();
+        vm.run(&program);
+        // the flag is off at the end, so a later line would start disabled
+        assert!(!vm.machine().flags[ENABLED_FLAG]);
+        let enabled: Vec<i64> = vm
+            .trace()
+            .iter()
+            .filter(|step| step.machine.flags[ENABLED_FLAG])
+            .map(|step| step.machine.registers[TOTAL_REG])
+            .collect();
+        assert_eq!(vec![8, 8, 48], enabled);
+
+        assert_eq!(0, do_math("mul(1234,5)", false));
+        assert_eq!(6, do_math("mul(1234,5)mul(2,3)", false));
     }
 }
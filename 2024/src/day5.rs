@@ -1,20 +1,26 @@
-use std::{cmp::Ordering, str::FromStr};
+use aoc_common::{
+    checked::Context,
+    graph::Graph,
+    input::sections,
+    parse::{unsigned, Parser},
+};
 
 use crate::utils::read_lines;
 
 #[derive(Debug)]
 struct RulesAndUpdates {
-    rules: Vec<OrderRule>,
+    rules: Graph<u16>,
     updates: Vec<PageUpdates>,
 }
 
 type PageUpdates = Vec<u16>;
-type OrderRule = (u16, u16);
+
+const DAY: Context = Context::day(5);
 
 impl RulesAndUpdates {
     fn new() -> Self {
         RulesAndUpdates {
-            rules: vec![],
+            rules: Graph::new(),
             updates: vec![],
         }
     }
@@ -31,55 +37,42 @@ pub fn run() {
 }
 
 fn part_1(input: &RulesAndUpdates) -> u16 {
-    input
-        .updates
-        .iter()
-        .filter(|update| is_valid(update, &input.rules))
-        .map(get_mid)
-        .sum()
+    DAY.part(1).sum(
+        input
+            .updates
+            .iter()
+            .filter(|update| is_valid(update, &input.rules))
+            .map(get_mid),
+    )
 }
 
 fn part_2(input: &RulesAndUpdates) -> u16 {
-    input
-        .updates
-        .clone()
-        .iter_mut()
-        .filter(|update| !is_valid(update, &input.rules))
-        .map(|update| {
-            fix_update(update, &input.rules);
-            get_mid(update)
-        })
-        .sum()
+    DAY.part(2).sum(
+        input
+            .updates
+            .clone()
+            .iter_mut()
+            .filter(|update| !is_valid(update, &input.rules))
+            .map(|update| {
+                fix_update(update, &input.rules);
+                get_mid(update)
+            }),
+    )
 }
 
-fn is_valid(page_update: &PageUpdates, rules: &[OrderRule]) -> bool {
-    for left in 0..page_update.len() - 1 {
-        for right in left + 1..page_update.len() {
-            let violations = rules
-                .iter()
-                .any(|rule| rule.0 == page_update[right] && rule.1 == page_update[left]);
-            if violations {
-                return false;
-            }
-        }
-    }
-
-    true
+fn is_valid(page_update: &PageUpdates, rules: &Graph<u16>) -> bool {
+    let position = |page: &u16| page_update.iter().position(|p| p == page);
+    rules
+        .subgraph(page_update)
+        .edges()
+        .all(|(before, after)| position(before) < position(after))
 }
 
-fn fix_update(page_update: &mut PageUpdates, rules: &[OrderRule]) {
-    page_update.sort_by(|left, right| {
-        for rule in rules {
-            if rule.0 == *left && rule.1 == *right {
-                return Ordering::Less;
-            }
-            if rule.0 == *right && rule.1 == *left {
-                return Ordering::Greater;
-            }
-        }
-
-        Ordering::Equal
-    });
+fn fix_update(page_update: &mut PageUpdates, rules: &Graph<u16>) {
+    *page_update = rules
+        .subgraph(page_update.iter())
+        .topological_sort()
+        .unwrap_or_else(|cycle| panic!("rules for {page_update:?} have a {cycle}"));
 }
 
 fn get_mid(page_update: &PageUpdates) -> u16 {
@@ -90,27 +83,27 @@ fn parse_input<F, E>(f: F) -> RulesAndUpdates
 where
     F: FnOnce() -> Result<std::io::Lines<std::io::BufReader<std::fs::File>>, E>,
 {
-    let lines_result = f();
     let mut input = RulesAndUpdates::new();
-    let mut rules = true;
-    if let Ok(lines) = lines_result {
-        for line in lines.map_while(Result::ok) {
-            if line.trim().is_empty() {
-                rules = false;
-                continue;
-            }
-
-            if rules {
-                if let Some((left, right)) = line.split_once('|') {
-                    input
-                        .rules
-                        .push((u16::from_str(left).unwrap(), u16::from_str(right).unwrap()))
-                }
-            } else {
-                input
-                    .updates
-                    .push(line.split(',').map(|s| s.parse().unwrap()).collect());
-            }
+    if let Ok(lines) = f() {
+        let lines: Vec<String> = lines.map_while(Result::ok).collect();
+        let mut blocks = sections(lines.iter().map(String::as_str));
+
+        if let Some(rules) = blocks.next() {
+            let rule =
+                (unsigned::<u16>(), '|', unsigned()).map(|(before, _, after)| (before, after));
+            input.rules = rules
+                .lines
+                .iter()
+                .map(|line| rule.parse(line).unwrap())
+                .collect();
+        }
+        if let Some(updates) = blocks.next() {
+            let update = unsigned().sep_by(',');
+            input.updates = updates
+                .lines
+                .iter()
+                .map(|line| update.parse(line).unwrap())
+                .collect();
         }
     }
 
@@ -122,7 +115,6 @@ mod tests {
     use super::*;
 
     #[test]
-    #[ignore = "reason"]
     fn sample() {
         let input = parse_input(|| read_lines("5_sample"));
         let part1 = part_1(&input);
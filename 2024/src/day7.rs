@@ -1,5 +1,15 @@
 use std::io::Result;
 
+use aoc_common::{
+    digits,
+    iter::{
+        FoldWhile::{Continue, Done},
+        IterExt,
+    },
+    parallel::ParallelSlice,
+    parse::{unsigned, Parser},
+};
+
 use crate::utils::read_lines;
 
 #[derive(Debug)]
@@ -16,17 +26,12 @@ enum Operation {
 }
 
 impl Operation {
-    fn run(&self, left: u64, right: u64) -> u64 {
+    /// `None` when the result doesn't fit in a `u64`, which also means it's past any target.
+    fn run(&self, left: u64, right: u64) -> Option<u64> {
         match self {
-            Self::Add => left + right,
-            Self::Multply => left * right,
-            Self::Concat => {
-                let mut pow = 10;
-                while right >= pow {
-                    pow *= 10;
-                }
-                left * pow + right
-            }
+            Self::Add => left.checked_add(right),
+            Self::Multply => left.checked_mul(right),
+            Self::Concat => digits::checked_concat(left, right),
         }
     }
 }
@@ -34,35 +39,26 @@ impl Operation {
 impl Equation {
     fn is_valid(&self, ops: &[Operation]) -> bool {
         let combos = self.values.len() - 1;
-        let combinations = get_combos(combos, ops);
-        // let mut combinations = get_combos(combos, ops);
-
-        // println!("result: {}, values: {:?}", self.result, self.values);
-        // println!("combinations: {:?}", combinations);
 
-        combinations.iter().any(|operations| {
-            // combinations.any(|operations| {
+        let mut operations = ops.iter().cartesian_power(combos);
+        while let Some(picks) = operations.next_indices() {
             let mut val_iter = self.values.iter();
             let initial = val_iter.next().unwrap().to_owned();
 
+            // none of the operations make the total smaller, so stop once it's too big
             let operation_result = val_iter
-                .zip(operations.clone())
-                // .fold_while(initial, |acc, (x, op)| {
-                //     if acc > self.result {
-                //         Done(acc)
-                //     } else {
-                //         Continue(op.run(acc, *x))
-                //     }
-                // })
-                // .into_inner();
-                .fold(initial, |acc, (x, op)| op.run(acc, *x));
-
-            // println!(
-            //     "values: {:?}, operations: {:?}, result: {}, expected result: {}",
-            //     self.values, operations, operation_result, self.result
-            // );
-            operation_result == self.result
-        })
+                .zip(picks)
+                .fold_while(Some(initial), |acc, (x, op)| match acc {
+                    Some(acc) if acc <= self.result => Continue(ops[*op].run(acc, *x)),
+                    _ => Done(None),
+                })
+                .into_inner();
+
+            if operation_result == Some(self.result) {
+                return true;
+            }
+        }
+        false
     }
 }
 
@@ -80,21 +76,25 @@ pub fn run() {
 fn part1(equations: &[Equation]) -> u64 {
     let ops = vec![Operation::Add, Operation::Multply];
 
-    equations
-        .iter()
-        .filter(|equation| equation.is_valid(&ops))
-        .map(|equation| equation.result)
-        .sum()
+    equations.par_sum(|equation| {
+        if equation.is_valid(&ops) {
+            equation.result
+        } else {
+            0
+        }
+    })
 }
 
 fn part2(equations: &[Equation]) -> u64 {
     let ops = vec![Operation::Add, Operation::Multply, Operation::Concat];
 
-    equations
-        .iter()
-        .filter(|equation| equation.is_valid(&ops))
-        .map(|equation| equation.result)
-        .sum()
+    equations.par_sum(|equation| {
+        if equation.is_valid(&ops) {
+            equation.result
+        } else {
+            0
+        }
+    })
 }
 
 fn parse_input(file: &str) -> Vec<Equation> {
@@ -102,15 +102,9 @@ fn parse_input(file: &str) -> Vec<Equation> {
 
     if let Ok(lines) = read_lines(file) {
         for line in lines.map_while(Result::ok) {
-            if let Some((result, values_str)) = line.split_once(':') {
-                equations.push(Equation {
-                    result: result.parse().unwrap(),
-                    values: values_str
-                        .trim()
-                        .split(' ')
-                        .map(|x| x.parse().unwrap())
-                        .collect(),
-                });
+            if let Ok((result, _, values)) = (unsigned(), ": ", unsigned().sep_by(' ')).parse(&line)
+            {
+                equations.push(Equation { result, values });
             }
         }
     }
@@ -118,25 +112,6 @@ fn parse_input(file: &str) -> Vec<Equation> {
     equations
 }
 
-fn get_combos(count: usize, ops: &[Operation]) -> Vec<Vec<&Operation>> {
-    if count <= 1 {
-        return ops.iter().map(|op| vec![op]).collect();
-    }
-    let smaller_combos = get_combos(count - 1, ops);
-
-    let mut ret: Vec<Vec<&Operation>> = Vec::with_capacity(ops.len());
-
-    for op in ops {
-        let combos = smaller_combos.clone();
-        for mut combo in combos {
-            combo.push(op);
-            ret.push(combo);
-        }
-    }
-
-    ret
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,8 +142,21 @@ mod tests {
 
         assert!(long_equation.is_valid(ops));
         assert!(longer_equation.is_valid(ops));
-        // for combo in get_combos(3, ops) {
-        //     println!("{:?}", combo);
-        // }
+    }
+
+    #[test]
+    fn overflow() {
+        let ops = &[Operation::Add, Operation::Multply, Operation::Concat];
+        let too_big = Equation {
+            values: vec![4294967296, 4294967296, 1],
+            result: u64::MAX,
+        };
+        assert!(!too_big.is_valid(ops));
+
+        let just_fits = Equation {
+            values: vec![1844674407370955161, 5],
+            result: u64::MAX,
+        };
+        assert!(just_fits.is_valid(ops));
     }
 }
@@ -1,30 +1,31 @@
+use aoc_common::FromLine;
+
 use crate::utils::read_lines;
 
-struct Turn {
-    amount: i32,
+#[derive(FromLine)]
+enum Turn {
+    #[pattern("L{0}")]
+    Left(i32),
+    #[pattern("R{0}")]
+    Right(i32),
 }
 
 struct Playbook {
     turns: Vec<Turn>,
 }
 
-impl From<&str> for Turn {
-    fn from(value: &str) -> Self {
-        let (dir, amt) = value.split_at(1);
-        let amount = amt.parse::<i32>().unwrap();
-        let amount = match dir {
-            "R" => amount,
-            "L" => -amount,
-            _ => panic!("aaahhhhh"),
-        };
-
-        Self { amount }
+impl Turn {
+    fn amount(&self) -> i32 {
+        match self {
+            Turn::Left(amount) => -amount,
+            Turn::Right(amount) => *amount,
+        }
     }
 }
 
 impl From<Vec<String>> for Playbook {
     fn from(values: Vec<String>) -> Self {
-        let turns = values.iter().map(|v| Turn::from(v.as_str())).collect();
+        let turns = values.iter().map(|v| v.parse().unwrap()).collect();
         Self { turns }
     }
 }
@@ -34,7 +35,7 @@ impl Playbook {
         let mut zeros: usize = 0;
         let mut current_position = 50;
         for turn in &self.turns {
-            current_position = (current_position + turn.amount) % 100;
+            current_position = (current_position + turn.amount()) % 100;
             if current_position == 0 {
                 zeros += 1;
             }
@@ -47,7 +48,7 @@ impl Playbook {
         let mut zeros: usize = 0;
         let mut curr_pos = 50;
         for turn in &self.turns {
-            let new_pos = curr_pos + turn.amount;
+            let new_pos = curr_pos + turn.amount();
             if new_pos <= 0 && curr_pos != 0 {
                 zeros += 1;
             }
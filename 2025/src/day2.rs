@@ -1,5 +1,7 @@
 use std::ops::RangeInclusive;
 
+use aoc_common::{digits, parallel::ParallelSlice};
+
 use crate::utils::read_lines;
 
 struct Products {
@@ -22,52 +24,26 @@ impl From<&str> for Products {
 
 impl Products {
     fn invalid_ids(&self) -> Vec<u64> {
-        let mut products: Vec<u64> = vec![];
-        for range in &self.ranges {
-            for id in range.clone() {
-                let product_id = id.to_string();
-                let (first, second) = product_id.split_at(product_id.len() / 2);
-                if first == second {
-                    products.push(id);
-                }
-            }
-        }
-
-        products
+        self.invalid(|id| {
+            let len = digits::count(id);
+            len.is_multiple_of(2) && Self::repeats(id, len / 2)
+        })
     }
 
-    fn repeats(input: &str, frequency: usize) -> bool {
-        let (initial, mut remaining) = input.split_at(frequency);
-
-        while !remaining.is_empty() {
-            let (part, rem) = remaining.split_at(frequency);
-            if part != initial {
-                return false;
-            }
-            remaining = rem;
-        }
-
-        true
+    fn repeats(id: u64, frequency: u32) -> bool {
+        digits::repeats(id, frequency)
     }
 
     fn invalid_ids_pt2(&self) -> Vec<u64> {
-        let mut products: Vec<u64> = vec![];
-        for range in &self.ranges {
-            for id in range.clone() {
-                let product_id = id.to_string();
-                for len in 1..=product_id.len() / 2 {
-                    if product_id.len() % len != 0 {
-                        continue;
-                    }
-                    if Self::repeats(&product_id, len) {
-                        products.push(id);
-                        break;
-                    }
-                }
-            }
-        }
-
-        products
+        self.invalid(digits::is_repeated)
+    }
+
+    /// Every id in every range that `invalid` is true for, checking the ranges in parallel but
+    /// keeping them in order.
+    fn invalid(&self, invalid: impl Fn(u64) -> bool + Sync) -> Vec<u64> {
+        self.ranges
+            .par_map(|range| range.clone().filter(|id| invalid(*id)).collect::<Vec<_>>())
+            .concat()
     }
 }
 
@@ -104,9 +80,9 @@ mod tests {
         );
         assert_eq!(1227775554, sum_of_ids);
 
-        assert!(Products::repeats("1188511885", 5));
-        assert!(Products::repeats("11", 1));
-        assert!(Products::repeats("22", 1));
+        assert!(Products::repeats(1188511885, 5));
+        assert!(Products::repeats(11, 1));
+        assert!(Products::repeats(22, 1));
 
         let pt2 = products.invalid_ids_pt2();
         assert_eq!(
@@ -1,3 +1,5 @@
+use aoc_common::parallel::ParallelSlice;
+
 use crate::utils::read_lines;
 
 struct Bank {
@@ -74,17 +76,11 @@ impl Bank {
 
 impl Power {
     fn sum_joltages(&self) -> u64 {
-        let mut sum: u64 = 0;
-        for bank in &self.banks {
-            sum += bank.largest_joltage() as u64;
-        }
-        sum
+        self.banks.par_sum(|bank| bank.largest_joltage() as u64)
     }
 
     fn boosted(&self, magnitude: usize) -> u64 {
-        self.banks
-            .iter()
-            .fold(0, |acc, x| acc + x.joltage(magnitude))
+        self.banks.par_sum(|bank| bank.joltage(magnitude))
     }
 }
 
@@ -1,112 +1,75 @@
-use std::mem::replace;
+use aoc_common::{
+    automaton::{Automaton, Neighbourhood, Update},
+    bits::BitGrid,
+    grid::SURROUNDING,
+    input::Input,
+};
 
-use crate::utils::read_lines;
+use crate::utils::read_input;
 
 struct Warehouse {
-    grid: Vec<Vec<bool>>,
-    rows: usize,
-    columns: usize,
+    grid: BitGrid,
 }
 
 impl From<Vec<&str>> for Warehouse {
     fn from(value: Vec<&str>) -> Self {
-        let mut grid: Vec<Vec<bool>> = Vec::new();
-        let rows = value.len();
-        let columns = value.first().unwrap().len();
-        for line in value {
-            grid.push(line.chars().map(|c| c == '@').collect());
+        Warehouse {
+            grid: BitGrid::from_lines(value, |b| b == b'@'),
         }
+    }
+}
 
+impl From<&Input> for Warehouse {
+    fn from(input: &Input) -> Self {
         Warehouse {
-            grid,
-            rows,
-            columns,
+            grid: BitGrid::from_lines(input.lines(), |b| b == b'@'),
         }
     }
 }
 
 impl Warehouse {
     fn get(&self, row: isize, column: isize) -> bool {
-        // if row < 0 || column < 0 {
-        //     return false;
-        // }
-        // if row >= self.rows || column >= self.columns {
-        //     return false;
-        // }
-        *self
-            .grid
-            .get(row as usize)
-            .and_then(|row| row.get(column as usize))
-            .unwrap_or(&false)
+        row >= 0 && column >= 0 && self.grid.get(row as usize, column as usize)
     }
 
     fn get_adjacent_count(&self, row: isize, column: isize) -> usize {
-        [
-            (row - 1, column - 1),
-            (row - 1, column),
-            (row - 1, column + 1),
-            (row, column - 1),
-            (row, column + 1),
-            (row + 1, column - 1),
-            (row + 1, column),
-            (row + 1, column + 1),
-        ]
-        .iter()
-        .map(|(row, col)| self.get(*row, *col))
-        .filter(|x| *x)
-        .count()
+        SURROUNDING
+            .iter()
+            .filter(|(dr, dc)| self.get(row + dr, column + dc))
+            .count()
     }
 
-    fn count_accessible_rolls(&self) -> usize {
-        let mut count: usize = 0;
-        for row in 0..self.rows {
-            for col in 0..self.columns {
-                if self.get(row as isize, col as isize)
-                    && self.get_adjacent_count(row as isize, col as isize) < 4
-                {
-                    count += 1;
-                }
-            }
-        }
-
-        count
+    fn accessible_rolls(&self) -> BitGrid {
+        self.grid.neighbour_counts().fewer_than(4).and(&self.grid)
     }
 
-    fn remove(&mut self, row: usize, col: usize) {
-        let line = self.grid.get_mut(row).unwrap();
-        let _ = replace(&mut line[col], false);
+    fn count_accessible_rolls(&self) -> usize {
+        self.accessible_rolls().count()
     }
 
-    fn remove_accessible_rolls(&mut self) -> usize {
-        let mut count: usize = 0;
-        for row in 0..self.rows {
-            for col in 0..self.columns {
-                if self.get(row as isize, col as isize)
-                    && self.get_adjacent_count(row as isize, col as isize) < 4
-                {
-                    self.remove(row, col);
-                    count += 1;
-                }
-            }
-        }
+    /// Keeps removing rolls until none are accessible, and returns how many went.
+    fn remove_all_accessible_rolls(&mut self) -> usize {
+        let rolls = std::mem::replace(&mut self.grid, BitGrid::new(0, 0));
+        let mut automaton = Automaton::new(rolls, Neighbourhood::Moore, stays);
+        let removed = automaton.run_to_fixed_point(Update::Worklist).iter().sum();
 
-        count
+        self.grid = automaton.into_grid();
+        removed
     }
 }
 
-pub fn run() {
-    let lines: Vec<String> = read_lines("4").unwrap().map_while(Result::ok).collect();
+/// A roll can be taken away once fewer than four others are around it, and once it's gone it
+/// stays gone, so the order they're taken in doesn't matter.
+fn stays(roll: bool, around: &[bool]) -> bool {
+    roll && around.iter().filter(|r| **r).count() >= 4
+}
 
-    let mut warehouse = Warehouse::from(lines.iter().map(String::as_str).collect::<Vec<&str>>());
+pub fn run() {
+    let mut warehouse = Warehouse::from(&read_input("4").unwrap());
     let accessible = warehouse.count_accessible_rolls();
 
     println!("accessible: {accessible}");
-    let mut removed = warehouse.remove_accessible_rolls();
-    let mut total = removed;
-    while removed > 0 {
-        removed = warehouse.remove_accessible_rolls();
-        total += removed;
-    }
+    let total = warehouse.remove_all_accessible_rolls();
 
     println!("total removed: {total}");
 }
@@ -138,15 +101,10 @@ mod tests {
         assert_eq!(2, warehouse.get_adjacent_count(0, 0));
         assert_eq!(4, warehouse.get_adjacent_count(0, 1));
         assert_eq!(7, warehouse.get_adjacent_count(2, 1));
+        // off the edge still counts the rolls that are on it
+        assert_eq!(1, warehouse.get_adjacent_count(-1, 1));
 
         assert_eq!(13, warehouse.count_accessible_rolls());
-        let mut removed = warehouse.remove_accessible_rolls();
-        let mut total = removed;
-        while removed > 0 {
-            removed = warehouse.remove_accessible_rolls();
-            total += removed;
-        }
-
-        println!("removed a total of {total} rolls");
+        assert_eq!(43, warehouse.remove_all_accessible_rolls());
     }
 }
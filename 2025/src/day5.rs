@@ -1,6 +1,11 @@
 use std::{fmt::Display, ops::RangeInclusive};
 
-use crate::utils::read_lines;
+use aoc_common::{
+    input::{Input, Section, sections},
+    ints::ints_n,
+};
+
+use crate::utils::read_input;
 
 struct Inventory {
     fresh_ranges: Vec<RangeInclusive<u64>>,
@@ -9,21 +14,32 @@ struct Inventory {
 
 impl From<&Vec<&str>> for Inventory {
     fn from(value: &Vec<&str>) -> Self {
-        let fresh_ranges: Vec<RangeInclusive<u64>> = value
+        Self::from_sections(sections(value.iter().copied()))
+    }
+}
+
+impl From<&Input> for Inventory {
+    fn from(input: &Input) -> Self {
+        Self::from_sections(input.sections())
+    }
+}
+
+impl Inventory {
+    fn from_sections<'a>(mut blocks: impl Iterator<Item = Section<'a>>) -> Self {
+        let fresh_ranges: Vec<RangeInclusive<u64>> = blocks
+            .next()
+            .map_or(vec![], |ranges| ranges.lines)
             .iter()
-            .take_while(|l| !(**l).is_empty()) // hate this syntax
             .map(|l| {
-                let (lower, upper) = (*l).split_once("-").unwrap();
-                lower.parse().unwrap()..=upper.parse().unwrap()
+                let [lower, upper] = ints_n(l);
+                lower..=upper
             })
             .collect();
 
-        let ids: Vec<u64> = value
-            .iter()
-            .skip_while(|l| !(**l).is_empty())
-            .skip(1)
-            .map(|l| (*l).parse().unwrap())
-            .collect();
+        let ids: Vec<u64> = blocks
+            .next()
+            .map_or(Ok(vec![]), |ids| ids.parse_lines())
+            .unwrap();
 
         Self { fresh_ranges, ids }
     }
@@ -125,11 +141,10 @@ impl Inventory {
 }
 
 pub fn run() {
-    let lines: Vec<String> = read_lines("5").unwrap().map_while(Result::ok).collect();
-    let partially_owned = lines.iter().map(String::as_str).collect::<Vec<&str>>();
+    let input = read_input("5").unwrap();
 
-    let mut valid_inventory = Inventory::from(&partially_owned);
-    let mut my_inventory = Inventory::from(&partially_owned);
+    let mut valid_inventory = Inventory::from(&input);
+    let mut my_inventory = Inventory::from(&input);
 
     valid_inventory
         .fresh_ranges
@@ -1,4 +1,4 @@
-use std::ops::Range;
+use aoc_common::text::Block;
 
 use crate::utils::read_lines;
 
@@ -51,55 +51,33 @@ fn parse_lines(lines: &Vec<String>) -> (Vec<Vec<u64>>, Vec<Operation>) {
 }
 
 fn parse_lines_pt2(lines: &[String]) -> (Vec<Vec<u64>>, Vec<Operation>) {
-    let last_line = lines.iter().last().unwrap();
-    let (indexes, operations): (Vec<usize>, Vec<Operation>) = last_line
-        .char_indices()
-        .filter(|(_, char)| !char.is_whitespace())
-        .map(|(idx, char)| (idx, Operation::from(char)))
-        .unzip();
-    let mut ranges: Vec<Range<usize>> = vec![];
-    let mut first: usize = 0;
-    indexes.iter().skip(1).for_each(|last| {
-        ranges.push(first..(*last - 1));
-        first = *last;
-    });
-    ranges.push(first..last_line.len());
-
-    let mut slices: Vec<Vec<&str>> = vec![];
-
-    for line in lines
-        .iter()
-        .take_while(|l| l.chars().all(|x| x.is_whitespace() || x.is_numeric()))
-    {
-        let str = line.as_str();
-        for (idx, range) in ranges.iter().enumerate() {
-            let slice = &str[range.clone()];
-            if let Some(existing) = slices.get_mut(idx) {
-                existing.push(slice);
-            } else {
-                slices.push(vec![slice]);
-            }
-        }
-    }
+    let (last_line, number_lines) = lines.split_last().unwrap();
+    let operations: Vec<Operation> = last_line
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .map(Operation::from)
+        .collect();
 
-    let numbers: Vec<Vec<u64>> = slices.iter().map(|c| transform(c.to_vec())).collect();
+    let block = Block::new(number_lines.iter().map(String::as_str));
+    let numbers: Vec<Vec<u64>> = block
+        .column_groups()
+        .into_iter()
+        .map(|columns| transform(&block.columns(columns)))
+        .collect();
 
     (numbers, operations)
 }
 
-fn transform(chonk: Vec<&str>) -> Vec<u64> {
-    let width = chonk.first().unwrap().len();
-    let mut chars: Vec<Vec<char>> = vec![vec![]; width];
-    for line in &chonk {
-        for (idx, char) in line.char_indices() {
-            chars.get_mut(idx).unwrap().push(char);
-        }
-    }
-
-    chars
-        .iter()
-        .map(|c| c.iter().collect::<String>())
-        .map(|s| s.trim().parse::<u64>().unwrap())
+fn transform(chonk: &Block) -> Vec<u64> {
+    chonk
+        .transpose()
+        .rows()
+        .map(|digits| {
+            digits
+                .iter()
+                .filter(|d| d.is_ascii_digit())
+                .fold(0, |acc, d| acc * 10 + u64::from(d - b'0'))
+        })
         .collect()
 }
 
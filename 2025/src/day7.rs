@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use aoc_common::hash::FastMap;
 
 use crate::utils::read_all_lines;
 
-fn process_line(line: &str, beams: &mut HashMap<usize, usize>) -> u64 {
+fn process_line(line: &str, beams: &mut FastMap<usize, usize>) -> u64 {
     if beams.is_empty() {
         let index: usize = line
             .char_indices()
@@ -39,7 +39,7 @@ fn process_line(line: &str, beams: &mut HashMap<usize, usize>) -> u64 {
 }
 
 fn get_split_count(lines: &Vec<String>) -> (u64, u64) {
-    let mut beams: HashMap<usize, usize> = HashMap::new();
+    let mut beams: FastMap<usize, usize> = FastMap::default();
     let mut split_count = 0_u64;
     for line in lines {
         split_count += process_line(line, &mut beams);
@@ -73,7 +73,7 @@ mod tests {
     #[test]
     fn starts_correctly() {
         let input = read_all_lines("7_sample");
-        let mut beams = HashMap::new();
+        let mut beams = FastMap::default();
         let split_count = process_line(input.first().unwrap(), &mut beams);
 
         assert_eq!(Some(&1), beams.get(&7));
@@ -1,8 +1,11 @@
-use std::{collections::HashSet, fmt::Display};
+use std::fmt::Display;
+
+use aoc_common::{FromLine, hash::FastSet, kdtree::KdTree, point::Point3};
 
 use crate::utils::read_all_lines;
 
-#[derive(Eq, Hash, PartialEq, Clone, Debug)]
+#[derive(Eq, Hash, PartialEq, Clone, Debug, FromLine)]
+#[pattern("{x},{y},{z}")]
 struct Position {
     x: u64,
     y: u64,
@@ -11,18 +14,7 @@ struct Position {
 
 #[derive(Debug)]
 struct Circuit {
-    positions: HashSet<Position>,
-}
-
-impl From<&String> for Position {
-    fn from(value: &String) -> Self {
-        let mut values = value.split(',').map(|s| s.parse::<u64>().unwrap());
-        let x = values.next().unwrap();
-        let y = values.next().unwrap();
-        let z = values.next().unwrap();
-
-        Self { x, y, z }
-    }
+    positions: FastSet<Position>,
 }
 
 impl Display for Circuit {
@@ -44,6 +36,10 @@ impl Display for Position {
 }
 
 impl Position {
+    fn point(&self) -> Point3<i64> {
+        Point3::new(self.x as i64, self.y as i64, self.z as i64)
+    }
+
     fn distance(&self, other: &Position) -> f64 {
         let x_diff = self.x.abs_diff(other.x).pow(2);
         let y_diff = self.y.abs_diff(other.y).pow(2);
@@ -55,7 +51,7 @@ impl Position {
 
 impl Circuit {
     fn new(position_0: Position, position_1: Position) -> Self {
-        let mut positions = HashSet::new();
+        let mut positions = FastSet::default();
         positions.insert(position_0);
         positions.insert(position_1);
 
@@ -85,74 +81,28 @@ impl Circuit {
     }
 }
 
-fn closest<'a>(position: &'a Position, positions: &'a [Position]) -> (&'a Position, &'a Position) {
-    let closest = positions
-        .iter()
-        .filter(|p| *p != position)
-        .min_by_key(|p| position.distance(p) as u64)
-        .unwrap();
-    (position, closest)
-}
-
-fn build_shortest_circuits(positions: Vec<Position>) -> Vec<Circuit> {
+/// Wires up the `connections` closest pairs of junction boxes, biggest circuit first.
+fn build_shortest_circuits(positions: Vec<Position>, connections: usize) -> Vec<Circuit> {
+    let tree = KdTree::new(positions.iter().map(Position::point).collect());
     let mut circuits: Vec<Circuit> = vec![];
 
-    let mut pairs: Vec<_> = positions.iter().map(|p| closest(p, &positions)).collect();
-    pairs.sort_by(|a, b| a.0.distance(a.1).total_cmp(&(b.0.distance(b.1))));
-
-    for pair in &pairs {
-        println!(
-            "pair: {}, {}: distance: {}",
-            pair.0,
-            pair.1,
-            pair.0.distance(pair.1)
-        );
-    }
-
-    for (a, b) in pairs {
-        if let Some(circuit) = circuits.iter_mut().find(|c| c.contains(a) || c.contains(b)) {
-            circuit.add_position(a.clone());
-            circuit.add_position(b.clone());
-        } else {
-            circuits.push(Circuit::new(a.clone(), b.clone()));
+    for (_, i, j) in tree.closest_pairs().take(connections) {
+        let (a, b) = (&positions[i], &positions[j]);
+        let circuit_a = circuits.iter().position(|c| c.contains(a));
+        let circuit_b = circuits.iter().position(|c| c.contains(b));
+
+        match (circuit_a, circuit_b) {
+            (Some(x), Some(y)) if x == y => {}
+            (Some(x), Some(y)) => {
+                let other = circuits.swap_remove(x.max(y));
+                circuits[x.min(y)].combine(other);
+            }
+            (Some(x), None) => circuits[x].add_position(b.clone()),
+            (None, Some(y)) => circuits[y].add_position(a.clone()),
+            (None, None) => circuits.push(Circuit::new(a.clone(), b.clone())),
         }
     }
 
-    // for position in &positions {
-    //     let closest = positions
-    //         .iter()
-    //         .filter(|p| *p != position)
-    //         .min_by_key(|p| position.distance(p) as u64)
-    //         .unwrap();
-    //
-    //     println!("closest to {position} is {closest}");
-    //     if let Some(circuit) = circuits.iter_mut().find(|c| c.contains(closest)) {
-    //         circuit.add_position(position.clone());
-    //     } else {
-    //         circuits.push(Circuit::new(position.clone()));
-    //     }
-    // }
-
-    // let positions: Vec<Position> = circuits
-    //     .iter()
-    //     .filter(|c| c.positions.len() == 1)
-    //     .map(|c| c.positions.iter().collect::<Vec<&Position>>())
-    //     .map(|p| (*p.first().unwrap()).clone())
-    //     .collect();
-    //
-    // for position in positions {
-    //     let closest = circuits
-    //         .iter_mut()
-    //         .filter(|c| c.positions.len() > 1)
-    //         .min_by(|a, b| {
-    //             a.shortest_distance(&position)
-    //                 .total_cmp(&b.shortest_distance(&position))
-    //         })
-    //         .unwrap();
-    //     println!("adding {:?} to {closest}", position);
-    //     closest.add_position(position);
-    // }
-    // circuits.retain(|c| c.positions.len() > 1);
     circuits.sort_by_key(|c| c.positions.len());
     circuits.reverse();
 
@@ -161,8 +111,8 @@ fn build_shortest_circuits(positions: Vec<Position>) -> Vec<Circuit> {
 
 pub fn run() {
     let lines = read_all_lines("8");
-    let positions: Vec<Position> = lines.iter().map(Position::from).collect();
-    let circuits = build_shortest_circuits(positions);
+    let positions: Vec<Position> = lines.iter().map(|l| l.parse().unwrap()).collect();
+    let circuits = build_shortest_circuits(positions, 1000);
     let top_3 = circuits
         .iter()
         .take(3)
@@ -177,11 +127,10 @@ mod tests {
     use super::*;
 
     #[test]
-    #[ignore = "it doesnt work yet"]
     fn sample() {
         let lines = read_all_lines("8_sample");
-        let positions: Vec<Position> = lines.iter().map(Position::from).collect();
-        let circuits = build_shortest_circuits(positions);
+        let positions: Vec<Position> = lines.iter().map(|l| l.parse().unwrap()).collect();
+        let circuits = build_shortest_circuits(positions, 10);
         // for circ in &circuits {
         //     println!("circuit: {circ}");
         // }
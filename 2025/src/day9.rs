@@ -1,23 +1,29 @@
-use std::{collections::VecDeque, fmt::Debug, str::FromStr};
+use aoc_common::{
+    checked::Context,
+    compress::Compressed,
+    grid::{Cells, Grid, ORTHOGONAL},
+    ints::{ParseInt, ints_n},
+    iter::IterExt,
+    point::Point2,
+    polygon::Polygon,
+    prefix::PrefixSum2D,
+};
 
 use crate::utils::read_all_lines;
 
 type Point = (u64, u64);
 
-fn parse_input<T>(file: &str) -> Vec<(T, T)>
-where
-    T: FromStr,
-    T::Err: Debug,
-{
+const DAY: Context = Context::day(9);
+
+fn parse_input<T: ParseInt>(file: &str) -> Vec<(T, T)> {
     read_all_lines(file)
         .iter()
-        .map(|l| l.split_once(",").unwrap())
-        .map(|(x, y)| (x.parse::<T>().unwrap(), y.parse::<T>().unwrap()))
+        .map(|l| ints_n(l).into())
         .collect()
 }
 
 fn calc_area(p1: &Point, p2: &Point) -> u64 {
-    (p1.0.abs_diff(p2.0) + 1) * (p1.1.abs_diff(p2.1) + 1)
+    (DAY.wrap(p1.0.abs_diff(p2.0) + 1) * (p1.1.abs_diff(p2.1) + 1)).get()
 }
 
 fn find_max_area(points: &[Point]) -> u64 {
@@ -30,51 +36,69 @@ fn find_max_area(points: &[Point]) -> u64 {
     max_area
 }
 
-fn point_in_rect(point: &Point, rectangle: (&Point, &Point)) -> bool {
-    if point == rectangle.0 || point == rectangle.1 {
-        return false;
+/// Everything reachable from the top left corner without crossing a wall.
+fn flood_outside(walls: &Grid<bool>) -> Grid<bool> {
+    let mut outside = Grid::new(walls.width(), walls.height(), false);
+    let mut stack = vec![(0, 0)];
+    while let Some(pos) = stack.pop() {
+        if walls.cell(pos) == Some(true) || outside.cell(pos) == Some(true) {
+            continue;
+        }
+        outside.set_cell(pos, true);
+        stack.extend(walls.neighbours(pos, &ORTHOGONAL));
     }
-    let range_x = rectangle.0.0.min(rectangle.1.0) + 1..rectangle.0.0.max(rectangle.1.0);
-    let range_y = rectangle.0.1.min(rectangle.1.1) + 1..rectangle.0.1.max(rectangle.1.1);
-
-    range_x.contains(&point.0) && range_y.contains(&point.1)
+    outside
 }
 
-fn get_options(start: &Point, points: &[Point]) -> Vec<Point> {
-    let mut options = vec![];
-    for point in points.iter().filter(|p| start != *p) {
-        if points
+/// Like [`find_max_area`], but the whole rectangle has to be red or green: inside the loop the
+/// red tiles make, or on it.
+///
+/// The coordinates are far too big for a real grid, so the loop is drawn on a compressed one
+/// instead, with a ring of padding so the flood fill can get all the way around. That only works
+/// because the loop is rectilinear, so every edge fills a whole row or column of cells. After that
+/// every rectangle is two prefix sum lookups: is any of it outside, and how big is it really.
+fn find_restricted_max_area(input: &[Point]) -> u64 {
+    let polygon = Polygon::new(
+        input
             .iter()
-            .filter(|p| *p != point)
-            .filter(|p| *p != start)
-            .all(|p| !point_in_rect(p, (start, point)))
-        {
-            options.push(*point);
-        } else {
-            let invalid_points: Vec<_> = points
-                .iter()
-                .filter(|p| *p != point)
-                .filter(|p| *p != start)
-                .filter(|p| point_in_rect(p, (start, point)))
-                .collect();
-            println!(
-                "for rectangle: {:?} - {:?}, invalid points found: {:?}",
-                start, point, invalid_points
-            );
+            .map(|(x, y)| Point2::new(*x as i64, *y as i64))
+            .collect(),
+    );
+    assert!(
+        polygon.is_rectilinear(),
+        "the red tiles should only turn corners"
+    );
+    let red = polygon.vertices();
+    let (min_x, max_x) = red.iter().map(|p| p.x).min_max().unwrap();
+    let (min_y, max_y) = red.iter().map(|p| p.y).min_max().unwrap();
+    let padding = [
+        Point2::new(min_x - 1, min_y - 1),
+        Point2::new(max_x + 1, max_y + 1),
+    ];
+    let compressed = Compressed::new(red.iter().chain(&padding), true);
+
+    let mut walls = Grid::new(compressed.width(), compressed.height(), false);
+    for (a, b) in polygon.edges() {
+        let (a, b) = (compressed.cell(&a).unwrap(), compressed.cell(&b).unwrap());
+        for row in a.0.min(b.0)..=a.0.max(b.0) {
+            for col in a.1.min(b.1)..=a.1.max(b.1) {
+                walls[(row, col)] = true;
+            }
         }
     }
-    options
-}
 
-fn find_restricted_max_area(input: &[Point]) -> u64 {
-    let mut max_area = 0_u64;
-    let points: VecDeque<_> = input.iter().collect();
+    let outside = PrefixSum2D::new(&flood_outside(&walls));
+    let areas = PrefixSum2D::new(&compressed.weights());
 
-    // for a in points {
-    //     for opt in get_options(a, points) {
-    //         max_area = calc_area(a, &opt).max(max_area);
-    //     }
-    // }
+    let mut max_area = 0_u64;
+    for (i, a) in red.iter().enumerate() {
+        for b in &red[i + 1..] {
+            let (a, b) = (compressed.cell(a).unwrap(), compressed.cell(b).unwrap());
+            if outside.sum_inclusive(a, b) == 0 {
+                max_area = max_area.max(areas.sum_inclusive(a, b) as u64);
+            }
+        }
+    }
 
     max_area
 }
@@ -84,6 +108,9 @@ pub fn run() {
 
     let max_area = find_max_area(&points);
     println!("max area: {max_area}");
+
+    let restricted_area = find_restricted_max_area(&points);
+    println!("restricted max area: {restricted_area}");
 }
 
 #[cfg(test)]
@@ -96,22 +123,7 @@ mod tests {
         let max_area = find_max_area(&points);
         assert_eq!(50, max_area);
 
-        let options = get_options(&(9, 7), &points);
-        println!("options: {:?}", options);
-        assert!(!options.is_empty());
         let restricted_area = find_restricted_max_area(&points);
         assert_eq!(24, restricted_area);
     }
-
-    #[test]
-    fn rectangle_bounds() {
-        // for rectangle: (9, 7) - (11, 1), invalid points found: [(11, 7), (9, 5)]
-        // assert!(!point_in_rect(&(11, 7), (&(9, 7), &(11, 1))));
-        let rect = (&(2, 2), &(4, 4));
-        assert!(point_in_rect(&(3, 3), rect));
-        assert!(!point_in_rect(&(2, 2), rect));
-        assert!(!point_in_rect(&(2, 4), rect));
-        assert!(!point_in_rect(&(4, 2), rect));
-        assert!(!point_in_rect(&(4, 4), rect));
-    }
 }
@@ -2,6 +2,8 @@ use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
 
+use aoc_common::input::Input;
+
 /// Reads lines from the inputs folder. Assumes files are type ".txt" and have a prefix of "day".
 ///
 /// # Examples
@@ -24,3 +26,8 @@ pub fn read_all_lines(day: &str) -> Vec<String> {
         .map_while(Result::ok)
         .collect()
 }
+
+/// Reads a whole file from the inputs folder in one go. Same naming as [`read_lines`].
+pub fn read_input(day: &str) -> io::Result<Input> {
+    Input::read(format!("./inputs/day{day}.txt"))
+}
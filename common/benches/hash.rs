@@ -0,0 +1,121 @@
+//! `cargo bench --bench hash`: the default SipHash against `FastMap`/`FastSet` on the same kind of
+//! work the hot days do.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, RandomState},
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use aoc_common::{digits, hash::FxBuildHasher};
+
+const RUNS: u32 = 20;
+
+fn time(f: impl Fn() -> u64) -> Duration {
+    black_box(f());
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(f());
+    }
+    start.elapsed() / RUNS
+}
+
+fn compare(name: &str, sip: impl Fn() -> u64, fx: impl Fn() -> u64) {
+    assert_eq!(sip(), fx(), "{name} disagrees");
+    let (sip, fx) = (time(sip), time(fx));
+    println!(
+        "{name:<12} sip {sip:>10.2?}   fx {fx:>10.2?}   {:.1}x",
+        sip.as_secs_f64() / fx.as_secs_f64()
+    );
+}
+
+/// 2024 day 11: the `(blinks, stone)` cache.
+fn blink<S: BuildHasher + Default>() -> u64 {
+    fn count<S: BuildHasher>(cache: &mut HashMap<(usize, u64), u64, S>, key: (usize, u64)) -> u64 {
+        if let Some(n) = cache.get(&key) {
+            return *n;
+        }
+        let (blinks, stone) = key;
+        let n = if blinks == 0 {
+            1
+        } else if stone == 0 {
+            count(cache, (blinks - 1, 1))
+        } else if let Some((first, second)) = digits::split_half(stone) {
+            count(cache, (blinks - 1, first)) + count(cache, (blinks - 1, second))
+        } else {
+            count(cache, (blinks - 1, stone * 2024))
+        };
+        cache.insert(key, n);
+        n
+    }
+
+    let mut cache = HashMap::with_hasher(S::default());
+    [4, 4841539, 66, 5279, 49207, 134, 609568, 0]
+        .iter()
+        .map(|stone| count(&mut cache, (75, *stone)))
+        .sum()
+}
+
+/// 2024 day 14: counting robots per tile, every second.
+fn robots<S: BuildHasher + Default>() -> u64 {
+    let (width, height) = (101, 103);
+    let mut busiest = 0;
+    for second in 0..200 {
+        let mut counts: HashMap<(usize, usize), usize, S> = HashMap::with_hasher(S::default());
+        for robot in 0..500_usize {
+            let x = (robot * 37 + second * (robot % 17 + 1)) % width;
+            let y = (robot * 53 + second * (robot % 13 + 1)) % height;
+            *counts.entry((x, y)).or_default() += 1;
+        }
+        busiest = busiest.max(*counts.values().max().unwrap());
+    }
+    busiest as u64
+}
+
+/// 2025 day 7: beams keyed by column, one line at a time.
+fn beams<S: BuildHasher + Default>() -> u64 {
+    let mut beams: HashMap<usize, u64, S> = HashMap::with_hasher(S::default());
+    beams.insert(500, 1);
+    for row in 0..500_usize {
+        for col in (0..1000).filter(|col| (col * 7 + row * 3) % 11 == 0) {
+            if let Some(incoming) = beams.remove(&col) {
+                *beams.entry(col - 1).or_default() += incoming;
+                *beams.entry(col + 1).or_default() += incoming;
+            }
+        }
+    }
+    beams.values().fold(0, |acc, n| acc.wrapping_add(*n))
+}
+
+/// 2025 day 8: circuits as sets of 3D positions.
+fn positions<S: BuildHasher + Default>() -> u64 {
+    fn key(i: u64) -> (u64, u64, u64) {
+        (
+            i * 7919 % 100_000,
+            i * 104_729 % 100_000,
+            i * 1299 % 100_000,
+        )
+    }
+
+    let mut circuit: HashSet<(u64, u64, u64), S> = HashSet::with_hasher(S::default());
+    (0..100_000).for_each(|i| {
+        circuit.insert(key(i));
+    });
+    (0..200_000).filter(|i| circuit.contains(&key(*i))).count() as u64
+}
+
+fn main() {
+    compare("day11 blink", blink::<RandomState>, blink::<FxBuildHasher>);
+    compare(
+        "day14 counts",
+        robots::<RandomState>,
+        robots::<FxBuildHasher>,
+    );
+    compare("day7 beams", beams::<RandomState>, beams::<FxBuildHasher>);
+    compare(
+        "day8 circuit",
+        positions::<RandomState>,
+        positions::<FxBuildHasher>,
+    );
+}
@@ -0,0 +1,347 @@
+//! `#[derive(FromLine)]`, used through `aoc_common::FromLine`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Fields, Index, LitStr, Member, Type, parse_macro_input,
+    spanned::Spanned,
+};
+
+/// Generates a `FromStr` impl that reads the type from a `#[pattern("...")]`.
+///
+/// Everything outside of braces has to match exactly. `{name}` fills the field `name`, `{0}` fills
+/// a tuple struct's first field, `{}` fills the next field in declaration order, and
+/// `{name.1}` fills one element of a tuple-typed field. A field's text runs up to the next
+/// literal in the pattern and is parsed with its own `FromStr`. Use `{{` and `}}` for braces.
+///
+/// Enums put a pattern on each variant, and the variant is picked by the literal its pattern
+/// starts with. At most one variant can start with a placeholder, and it's tried last.
+///
+/// ```ignore
+/// #[derive(FromLine)]
+/// #[pattern("p={position.0},{position.1} v={velocity.0},{velocity.1}")]
+/// struct Robot {
+///     position: (usize, usize),
+///     velocity: (i32, i32),
+/// }
+///
+/// #[derive(FromLine)]
+/// enum Turn {
+///     #[pattern("L{0}")]
+///     Left(i32),
+///     #[pattern("R{0}")]
+///     Right(i32),
+/// }
+/// ```
+#[proc_macro_derive(FromLine, attributes(pattern))]
+pub fn derive_from_line(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let pattern = find_pattern(&input.attrs, input.span())?;
+            parse_body(&data.fields, &pattern, quote!(Self))?
+        }
+        Data::Enum(data) => {
+            let mut prefixed = vec![];
+            let mut fallback = None;
+            for variant in &data.variants {
+                let pattern = find_pattern(&variant.attrs, variant.span())?;
+                let ident = &variant.ident;
+                let body = parse_body(&variant.fields, &pattern, quote!(Self::#ident))?;
+                match split_pattern(&pattern)?.first() {
+                    Some(Segment::Literal(prefix)) => prefixed.push((prefix.clone(), body)),
+                    _ if fallback.is_some() => {
+                        return Err(syn::Error::new(
+                            pattern.span(),
+                            "only one variant's pattern can start with a placeholder",
+                        ));
+                    }
+                    _ => fallback = Some(body),
+                }
+            }
+            // longest first, so `don't` gets a look before `do`
+            prefixed.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+            let expected = prefixed
+                .iter()
+                .map(|(prefix, _)| format!("{prefix:?}"))
+                .collect::<Vec<_>>()
+                .join(" or ");
+            let (prefixes, bodies): (Vec<_>, Vec<_>) = prefixed.into_iter().unzip();
+            let otherwise = fallback.unwrap_or_else(|| {
+                quote! {
+                    Err(::aoc_common::parse::Error {
+                        position: 0,
+                        expected: #expected.to_string(),
+                    })
+                }
+            });
+
+            quote! {
+                #(if __input.starts_with(#prefixes) {
+                    return #bodies;
+                })*
+                #otherwise
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                input.span(),
+                "FromLine can't be derived for unions",
+            ));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::aoc_common::parse::Error;
+
+            fn from_str(__input: &str) -> ::std::result::Result<Self, Self::Err> {
+                #body
+            }
+        }
+    })
+}
+
+fn find_pattern(attrs: &[Attribute], span: proc_macro2::Span) -> syn::Result<LitStr> {
+    attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("pattern"))
+        .ok_or_else(|| syn::Error::new(span, "missing #[pattern(\"...\")]"))?
+        .parse_args()
+}
+
+enum Segment {
+    Literal(String),
+    Placeholder(String),
+}
+
+fn split_pattern(pattern: &LitStr) -> syn::Result<Vec<Segment>> {
+    let error = |message| Err(syn::Error::new(pattern.span(), message));
+    let text = pattern.value();
+    let mut segments = vec![];
+    let mut literal = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return error("unclosed `{` in pattern"),
+                    }
+                }
+                if literal.is_empty() && matches!(segments.last(), Some(Segment::Placeholder(_))) {
+                    return error("two placeholders in a row need a literal between them");
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Placeholder(name.trim().to_string()));
+            }
+            '}' => return error("unmatched `}` in pattern, use `}}` for a literal brace"),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+/// A placeholder resolved against the fields: which field it fills, and which element of it
+/// when the field is a tuple.
+struct Slot {
+    field: usize,
+    element: Option<usize>,
+    ty: Type,
+}
+
+fn resolve(
+    placeholder: &str,
+    fields: &[(Member, Type)],
+    next: &mut usize,
+    pattern: &LitStr,
+) -> syn::Result<Slot> {
+    let error = |message: String| syn::Error::new(pattern.span(), message);
+
+    if placeholder.is_empty() {
+        let field = *next;
+        *next += 1;
+        let (_, ty) = fields
+            .get(field)
+            .ok_or_else(|| error("more `{}` placeholders than fields".to_string()))?;
+        return Ok(Slot {
+            field,
+            element: None,
+            ty: ty.clone(),
+        });
+    }
+
+    let (name, element) = match placeholder.split_once('.') {
+        Some((name, element)) => {
+            let element = element
+                .parse::<usize>()
+                .map_err(|_| error(format!("`{placeholder}` should look like `field.0`")))?;
+            (name, Some(element))
+        }
+        None => (placeholder, None),
+    };
+    let field = fields
+        .iter()
+        .position(|(member, _)| match member {
+            Member::Named(ident) => ident == name,
+            Member::Unnamed(index) => index.index.to_string() == name,
+        })
+        .ok_or_else(|| error(format!("no field named `{name}`")))?;
+
+    let ty = match (element, &fields[field].1) {
+        (None, ty) => ty.clone(),
+        (Some(element), Type::Tuple(tuple)) => tuple
+            .elems
+            .iter()
+            .nth(element)
+            .cloned()
+            .ok_or_else(|| error(format!("`{name}` has no element {element}")))?,
+        (Some(_), _) => return Err(error(format!("`{name}` isn't a tuple"))),
+    };
+
+    Ok(Slot { field, element, ty })
+}
+
+/// Code that parses `__input` with `pattern` and evaluates to `Ok(constructor { .. })`.
+fn parse_body(
+    fields: &Fields,
+    pattern: &LitStr,
+    constructor: TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let fields: Vec<(Member, Type)> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(i)),
+            };
+            (member, field.ty.clone())
+        })
+        .collect();
+
+    let segments = split_pattern(pattern)?;
+    let mut next = 0;
+    let mut slots = vec![];
+    let mut steps = vec![];
+
+    for (i, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Literal(literal) => steps.push(quote! {
+                let (_, __rest) = ::aoc_common::parse::Parser::parse_partial(&#literal, __rest)
+                    .map_err(|f| f.locate(__input))?;
+            }),
+            Segment::Placeholder(placeholder) => {
+                let slot = resolve(placeholder, &fields, &mut next, pattern)?;
+                let var = format_ident!("__slot{}", slots.len());
+                let ty = &slot.ty;
+                let terminator = match segments.get(i + 1) {
+                    Some(Segment::Literal(literal)) => quote!(Some(#literal)),
+                    _ => quote!(None),
+                };
+                steps.push(quote! {
+                    let (#var, __rest) = ::aoc_common::parse::Parser::parse_partial(
+                        &::aoc_common::parse::field::<#ty>(#terminator),
+                        __rest,
+                    )
+                    .map_err(|f| f.locate(__input))?;
+                });
+                slots.push(slot);
+            }
+        }
+    }
+
+    let mut values = vec![];
+    for (field, (member, _)) in fields.iter().enumerate() {
+        let filled: Vec<(usize, &Slot)> = slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.field == field)
+            .collect();
+        let var = |i: usize| format_ident!("__slot{i}");
+        let missing = |what: String| syn::Error::new(pattern.span(), what);
+
+        let value = match filled.as_slice() {
+            [] => {
+                return Err(missing(format!(
+                    "`{}` isn't in the pattern",
+                    quote!(#member)
+                )));
+            }
+            [(i, Slot { element: None, .. })] => {
+                let var = var(*i);
+                quote!(#var)
+            }
+            _ => {
+                let mut elements: Vec<(usize, usize)> = vec![];
+                for (i, slot) in &filled {
+                    match slot.element {
+                        Some(element) => elements.push((element, *i)),
+                        None => {
+                            return Err(missing(format!(
+                                "`{}` is in the pattern more than once",
+                                quote!(#member)
+                            )));
+                        }
+                    }
+                }
+                elements.sort();
+                let arity = match &fields[field].1 {
+                    Type::Tuple(tuple) => tuple.elems.len(),
+                    _ => 0,
+                };
+                if elements.len() != arity
+                    || elements
+                        .iter()
+                        .enumerate()
+                        .any(|(n, (element, _))| n != *element)
+                {
+                    return Err(missing(format!(
+                        "every element of `{}` has to be in the pattern exactly once",
+                        quote!(#member)
+                    )));
+                }
+                let vars = elements.iter().map(|(_, i)| var(*i));
+                quote!((#(#vars,)*))
+            }
+        };
+        values.push(quote!(#member: #value));
+    }
+
+    Ok(quote! {{
+        let __rest = __input;
+        #(#steps)*
+        ::aoc_common::parse::Parser::parse_partial(&::aoc_common::parse::end(), __rest)
+            .map_err(|f| f.locate(__input))?;
+        Ok(#constructor { #(#values),* })
+    }})
+}
@@ -0,0 +1,83 @@
+use std::fmt::Display;
+
+use crate::bigint::BigInt;
+
+/// A puzzle answer, whatever shape it comes in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Big(BigInt),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::Big(n) => write!(f, "{n}"),
+            Answer::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Answer::Int(value as i128)
+            }
+        }
+    )*};
+}
+
+from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        match i128::try_from(value) {
+            Ok(n) => Answer::Int(n),
+            Err(_) => Answer::Big(BigInt::from(value)),
+        }
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        match value.to_i128() {
+            Some(n) => Answer::Int(n),
+            None => Answer::Big(value),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        assert_eq!(Answer::Int(55312), Answer::from(55312_u64));
+        assert_eq!(Answer::Int(-3), Answer::from(-3_i32));
+        assert_eq!(Answer::Int(12), Answer::from(BigInt::from(12)));
+        assert_eq!("RFLK", Answer::from("RFLK").to_string());
+
+        let huge = BigInt::from(u128::MAX) * BigInt::from(10);
+        assert_eq!(Answer::Big(huge.clone()), Answer::from(huge));
+        assert_eq!(
+            "340282366920938463463374607431768211455",
+            Answer::from(u128::MAX).to_string()
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! Cellular automata: every cell's next value comes from its current value and its neighbours'.
+//!
+//! Only cells next to something that changed get looked at again, so a run that settles down
+//! gets cheaper as it goes instead of rescanning the whole grid every generation.
+
+use crate::{
+    bits::BitGrid,
+    grid::{Cells, Grid, ORTHOGONAL, Pos, SURROUNDING},
+};
+
+/// Which cells count as neighbours, as offsets from the cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The four orthogonal cells.
+    VonNeumann,
+    /// All eight surrounding cells.
+    Moore,
+    Custom(Vec<Pos>),
+}
+
+impl Neighbourhood {
+    pub fn offsets(&self) -> &[Pos] {
+        match self {
+            Neighbourhood::VonNeumann => &ORTHOGONAL,
+            Neighbourhood::Moore => &SURROUNDING,
+            Neighbourhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
+/// How changes made during a generation are seen by the rest of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Update {
+    /// Every cell in a generation sees the grid as it was when the generation started, and the
+    /// changes all land together at the end. The result doesn't depend on the order cells are
+    /// visited.
+    Synchronous,
+    /// Changes land straight away, so cells visited later in the same pass already see them.
+    /// Cheaper, and gives the same fixed point as [`Update::Synchronous`] for rules that only
+    /// ever push cells one way, like removing things that have too few neighbours.
+    Worklist,
+}
+
+/// A rectangle of cells an [`Automaton`] can run on.
+pub trait Board: Cells {
+    fn width(&self) -> usize;
+
+    fn height(&self) -> usize;
+}
+
+impl<T: Copy> Board for Grid<T> {
+    fn width(&self) -> usize {
+        Grid::width(self)
+    }
+
+    fn height(&self) -> usize {
+        Grid::height(self)
+    }
+}
+
+impl Board for BitGrid {
+    fn width(&self) -> usize {
+        BitGrid::width(self)
+    }
+
+    fn height(&self) -> usize {
+        BitGrid::height(self)
+    }
+}
+
+/// A grid and the rule that moves it on a generation. The rule gets a cell and its neighbours
+/// that are on the grid, in the order the neighbourhood lists them.
+pub struct Automaton<G, F> {
+    grid: G,
+    neighbourhood: Neighbourhood,
+    rule: F,
+}
+
+impl<G, F> Automaton<G, F>
+where
+    G: Board,
+    G::Cell: PartialEq,
+    F: Fn(G::Cell, &[G::Cell]) -> G::Cell,
+{
+    pub fn new(grid: G, neighbourhood: Neighbourhood, rule: F) -> Self {
+        Self {
+            grid,
+            neighbourhood,
+            rule,
+        }
+    }
+
+    pub fn grid(&self) -> &G {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> G {
+        self.grid
+    }
+
+    /// One synchronous generation over every cell. Returns how many cells changed.
+    pub fn step(&mut self) -> usize {
+        let everything = self.everything();
+        self.generation(&everything, Update::Synchronous).len()
+    }
+
+    /// Keeps going until a generation changes nothing, and returns how many cells changed in
+    /// each generation before that.
+    pub fn run_to_fixed_point(&mut self, update: Update) -> Vec<usize> {
+        let mut counts = vec![];
+        let mut queued = Grid::new(self.grid.width(), self.grid.height(), false);
+        let mut wave = self.everything();
+        loop {
+            let changed = self.generation(&wave, update);
+            if changed.is_empty() {
+                return counts;
+            }
+            counts.push(changed.len());
+
+            wave.clear();
+            for cell in changed {
+                for dependent in self.dependents(cell) {
+                    if !queued[dependent] {
+                        queued[dependent] = true;
+                        wave.push(dependent);
+                    }
+                }
+            }
+            for cell in &wave {
+                queued[*cell] = false;
+            }
+        }
+    }
+
+    fn everything(&self) -> Vec<(usize, usize)> {
+        let width = self.grid.width();
+        (0..self.grid.height())
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .collect()
+    }
+
+    fn at(&self, (row, col): (usize, usize)) -> G::Cell {
+        self.grid
+            .cell((row as isize, col as isize))
+            .expect("only cells on the grid get looked at")
+    }
+
+    /// Runs the rule over `cells`, and returns the ones that changed.
+    fn generation(&mut self, cells: &[(usize, usize)], update: Update) -> Vec<(usize, usize)> {
+        let mut around = Vec::with_capacity(self.neighbourhood.offsets().len());
+        let mut changed = vec![];
+        let mut pending = vec![];
+        for cell in cells {
+            around.clear();
+            around.extend(self.shifted(*cell, 1).map(|neighbour| self.at(neighbour)));
+            let current = self.at(*cell);
+            let next = (self.rule)(current, &around);
+            if next != current {
+                changed.push(*cell);
+                match update {
+                    Update::Synchronous => pending.push(next),
+                    Update::Worklist => self.set(*cell, next),
+                }
+            }
+        }
+
+        for (cell, next) in changed.iter().zip(pending) {
+            self.set(*cell, next);
+        }
+        changed
+    }
+
+    fn set(&mut self, (row, col): (usize, usize), value: G::Cell) {
+        self.grid.set_cell((row as isize, col as isize), value);
+    }
+
+    /// The cells that might change because `cell` did: itself, and everything that has it as a
+    /// neighbour.
+    fn dependents(&self, cell: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        std::iter::once(cell).chain(self.shifted(cell, -1))
+    }
+
+    /// `cell` moved by each offset in the neighbourhood, scaled by `sign`, where it's on the grid.
+    fn shifted(
+        &self,
+        (row, col): (usize, usize),
+        sign: isize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        self.neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(move |(dr, dc)| {
+                let row = row.checked_add_signed(dr * sign)?;
+                let col = col.checked_add_signed(dc * sign)?;
+                (row < height && col < width).then_some((row, col))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025 day 4
+    const ROLLS: [&str; 10] = [
+        "..@@.@@@@.",
+        "@@@.@.@.@@",
+        "@@@@@.@.@@",
+        "@.@@@@..@.",
+        "@@.@@@@.@@",
+        ".@@@@@@@.@",
+        ".@.@.@.@@@",
+        "@.@@@.@@@@",
+        ".@@@@@@@@.",
+        "@.@.@@@.@.",
+    ];
+
+    fn rolls() -> Grid<bool> {
+        Grid::from_lines(ROLLS, |b| b == b'@')
+    }
+
+    /// Anything with fewer than two others beside it wears away. Like the day's rule, cells only
+    /// ever go, so both kinds of update end up in the same place.
+    fn erode(cell: bool, around: &[bool]) -> bool {
+        cell && around.iter().filter(|c| **c).count() >= 2
+    }
+
+    #[test]
+    fn erosion() {
+        let mut eroding = Automaton::new(rolls(), Neighbourhood::VonNeumann, erode);
+        assert_eq!(
+            vec![11, 2, 1, 1, 1],
+            eroding.run_to_fixed_point(Update::Synchronous)
+        );
+        let synchronous = eroding.into_grid();
+
+        let mut eroding = Automaton::new(rolls(), Neighbourhood::VonNeumann, erode);
+        let counts = eroding.run_to_fixed_point(Update::Worklist);
+        assert_eq!(16, counts.iter().sum::<usize>());
+        assert!(counts.len() <= 5);
+        assert_eq!(&synchronous, eroding.grid());
+
+        // straight on a bit grid, without copying it into a `Grid` first
+
+        let bits = BitGrid::from_lines(ROLLS, |b| b == b'@');
+        let mut eroding = Automaton::new(bits, Neighbourhood::VonNeumann, erode);
+        assert_eq!(
+            16,
+            eroding
+                .run_to_fixed_point(Update::Worklist)
+                .iter()
+                .sum::<usize>()
+        );
+        let bits = eroding.into_grid();
+        assert_eq!(55, bits.count());
+        assert!(
+            synchronous
+                .iter()
+                .all(|((row, col), c)| bits.get(row, col) == *c)
+        );
+    }
+
+    #[test]
+    fn blinker() {
+        let line = Grid::from_lines([".....", ".....", ".###.", ".....", "....."], |b| b == b'#');
+        let life = |alive: bool, around: &[bool]| {
+            let n = around.iter().filter(|a| **a).count();
+            n == 3 || (alive && n == 2)
+        };
+        let mut automaton = Automaton::new(line.clone(), Neighbourhood::Moore, life);
+        assert_eq!(4, automaton.step());
+        assert!(automaton.grid()[(1, 2)] && automaton.grid()[(3, 2)]);
+        assert!(!automaton.grid()[(2, 1)]);
+        assert_eq!(4, automaton.step());
+        assert_eq!(&line, automaton.grid());
+    }
+
+    #[test]
+    fn custom() {
+        // each cell copies whatever is to its left
+        let row = Grid::from_lines(["#....."], |b| b == b'#');
+        let spread = |cell: bool, left: &[bool]| cell || left.first() == Some(&true);
+        let from_left = Neighbourhood::Custom(vec![(0, -1)]);
+
+        let mut automaton = Automaton::new(row.clone(), from_left.clone(), spread);
+        assert_eq!(
+            vec![1, 1, 1, 1, 1],
+            automaton.run_to_fixed_point(Update::Synchronous)
+        );
+
+        // visiting left to right, the worklist carries it all the way in one pass
+        let mut automaton = Automaton::new(row, from_left, spread);
+        assert_eq!(vec![5], automaton.run_to_fixed_point(Update::Worklist));
+        assert!(automaton.grid().row(0).iter().all(|c| *c));
+
+        let mut automaton = Automaton::new(
+            Grid::new(3, 3, 0),
+            Neighbourhood::VonNeumann,
+            |cell: u8, around: &[u8]| cell.max(around.len() as u8),
+        );
+        automaton.step();
+        assert_eq!(&[2, 3, 2], automaton.grid().row(0));
+        assert_eq!(4, automaton.grid()[(1, 1)]);
+    }
+}
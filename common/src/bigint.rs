@@ -0,0 +1,541 @@
+//! Arbitrary-precision integers, for answers that don't fit in a `u64`.
+
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// A signed integer of any size.
+///
+/// Stored as a sign and the magnitude in base 2^32 limbs, least significant first. The magnitude
+/// never has trailing zero limbs and zero is never negative, so the derived `Eq` and `Hash` work.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    limbs: Vec<u32>,
+}
+
+const CHUNK: u32 = 1_000_000_000;
+const CHUNK_DIGITS: usize = 9;
+
+fn trim(mut limbs: Vec<u32>) -> Vec<u32> {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    limbs
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0_u64;
+    for (i, limb) in long.iter().enumerate() {
+        let total = *limb as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        sum.push(total as u32);
+        carry = total >> 32;
+    }
+    if carry > 0 {
+        sum.push(carry as u32);
+    }
+    sum
+}
+
+/// `a - b`, where `a` is at least as big as `b`.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0_i64;
+    for (i, limb) in a.iter().enumerate() {
+        let mut total = *limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if total < 0 {
+            total += 1 << 32;
+            borrow = 1;
+        }
+        difference.push(total as u32);
+    }
+    trim(difference)
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut product = vec![0_u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0_u64;
+        for (j, y) in b.iter().enumerate() {
+            let total = *x as u64 * *y as u64 + product[i + j] as u64 + carry;
+            product[i + j] = total as u32;
+            carry = total >> 32;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+    trim(product)
+}
+
+/// `a * multiplier + addend`, in place.
+fn mul_add_small(a: &mut Vec<u32>, multiplier: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for limb in a.iter_mut() {
+        let total = *limb as u64 * multiplier as u64 + carry;
+        *limb = total as u32;
+        carry = total >> 32;
+    }
+    if carry > 0 {
+        a.push(carry as u32);
+    }
+}
+
+fn div_rem_small(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0_u32; a.len()];
+    let mut remainder = 0_u64;
+    for (i, limb) in a.iter().enumerate().rev() {
+        let current = (remainder << 32) | *limb as u64;
+        quotient[i] = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+    (trim(quotient), remainder as u32)
+}
+
+/// Shift-and-subtract long division, one bit at a time.
+fn div_rem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if b.len() == 1 {
+        let (quotient, remainder) = div_rem_small(a, b[0]);
+        return (quotient, trim(vec![remainder]));
+    }
+    if cmp_mag(a, b) == Ordering::Less {
+        return (vec![], a.to_vec());
+    }
+
+    let mut quotient = vec![0_u32; a.len()];
+    let mut remainder: Vec<u32> = vec![];
+    for bit in (0..a.len() * 32).rev() {
+        // remainder = remainder * 2 + next bit of a
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+        for limb in remainder.iter_mut() {
+            let top = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = top;
+        }
+        if carry > 0 {
+            remainder.push(carry);
+        }
+
+        if cmp_mag(&remainder, b) != Ordering::Less {
+            remainder = sub_mag(&remainder, b);
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+
+    (trim(quotient), remainder)
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, limbs: Vec<u32>) -> Self {
+        let limbs = trim(limbs);
+        Self {
+            negative: negative && !limbs.is_empty(),
+            limbs,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.limbs.clone())
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = Self::from(1_u8);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// Quotient and remainder, rounding towards zero like the primitive integers do.
+    ///
+    /// # Panics
+    ///
+    /// When `divisor` is zero.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+        let (quotient, remainder) = div_rem_mag(&self.limbs, &divisor.limbs);
+        (
+            Self::from_parts(self.negative != divisor.negative, quotient),
+            Self::from_parts(self.negative, remainder),
+        )
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0_u128, |acc, limb| (acc << 32) | *limb as u128);
+        if self.negative {
+            0_i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        self.to_i128().and_then(|n| u64::try_from(n).ok())
+    }
+}
+
+macro_rules! from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(value: $t) -> Self {
+                let mut value = value as u128;
+                let mut limbs = vec![];
+                while value > 0 {
+                    limbs.push(value as u32);
+                    value >>= 32;
+                }
+                Self::from_parts(false, limbs)
+            }
+        }
+    )*};
+}
+
+macro_rules! from_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(value: $t) -> Self {
+                let magnitude = BigInt::from((value as i128).unsigned_abs());
+                Self::from_parts(value < 0, magnitude.limbs)
+            }
+        }
+    )*};
+}
+
+from_unsigned!(u8, u16, u32, u64, u128, usize);
+from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.limbs, &other.limbs),
+            (true, true) => cmp_mag(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        let mut chunks = vec![];
+        let mut rest = self.limbs.clone();
+        while !rest.is_empty() {
+            let (quotient, chunk) = div_rem_small(&rest, CHUNK);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+
+        let sign = if self.negative { "-" } else { "" };
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{sign}{}", chunks.next().unwrap())?;
+        for chunk in chunks {
+            write!(f, "{chunk:0width$}", width = CHUNK_DIGITS)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl Display for ParseBigIntError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        let mut limbs = vec![];
+        for chunk in digits.as_bytes().chunks(CHUNK_DIGITS) {
+            let value = chunk.iter().fold(0, |acc, d| acc * 10 + (d - b'0') as u32);
+            mul_add_small(&mut limbs, 10_u32.pow(chunk.len() as u32), value);
+        }
+
+        Ok(Self::from_parts(negative, limbs))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        let negative = !self.negative;
+        Self::from_parts(negative, self.limbs)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_mag(&self.limbs, &other.limbs));
+        }
+        match cmp_mag(&self.limbs, &other.limbs) {
+            Ordering::Less => {
+                BigInt::from_parts(other.negative, sub_mag(&other.limbs, &self.limbs))
+            }
+            _ => BigInt::from_parts(self.negative, sub_mag(&self.limbs, &other.limbs)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &(-other)
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            mul_mag(&self.limbs, &other.limbs),
+        )
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+macro_rules! owned_ops {
+    ($($trait:ident $method:ident),*) => {$(
+        impl $trait for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $trait<&BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: &BigInt) -> BigInt {
+                (&self).$method(other)
+            }
+        }
+
+        impl $trait<BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                self.$method(&other)
+            }
+        }
+    )*};
+}
+
+owned_ops!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+macro_rules! assign_ops {
+    ($($trait:ident $method:ident $op:ident),*) => {$(
+        impl $trait<&BigInt> for BigInt {
+            fn $method(&mut self, other: &BigInt) {
+                *self = (&*self).$op(other);
+            }
+        }
+
+        impl $trait for BigInt {
+            fn $method(&mut self, other: BigInt) {
+                *self = (&*self).$op(&other);
+            }
+        }
+    )*};
+}
+
+assign_ops!(AddAssign add_assign add, SubAssign sub_assign sub, MulAssign mul_assign mul);
+
+impl Sum for BigInt {
+    fn sum<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a BigInt> for BigInt {
+    fn sum<I: Iterator<Item = &'a BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::zero(), |acc, x| acc + x)
+    }
+}
+
+impl Product for BigInt {
+    fn product<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::from(1_u8), |acc, x| acc * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A spread of values around the interesting limb boundaries, with both signs.
+    fn samples() -> Vec<i128> {
+        let mut values = vec![0, 1, 2, 9, 10, 1_000_000_000, u32::MAX as i128, 1 << 32];
+        values.extend([
+            u64::MAX as i128,
+            1 << 64,
+            10000000000000,
+            123456789012345678901234,
+        ]);
+        let mut seed = 0x2545f4914f6cdd1d_u64;
+        for _ in 0..20 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            values.push((seed >> (seed % 60)) as i128);
+        }
+        let negatives: Vec<i128> = values.iter().map(|v| -v).collect();
+        values.extend(negatives);
+        values
+    }
+
+    #[test]
+    fn sample() {
+        let big = |n: i128| BigInt::from(n);
+        let samples = samples();
+
+        for &a in &samples {
+            assert_eq!(a.to_string(), big(a).to_string());
+            assert_eq!(Ok(big(a)), a.to_string().parse());
+            assert_eq!(Some(a), big(a).to_i128());
+
+            for &b in &samples {
+                assert_eq!(a.cmp(&b), big(a).cmp(&big(b)), "{a} cmp {b}");
+                if let Some(sum) = a.checked_add(b) {
+                    assert_eq!(big(sum), big(a) + big(b), "{a} + {b}");
+                }
+                if let Some(difference) = a.checked_sub(b) {
+                    assert_eq!(big(difference), big(a) - big(b), "{a} - {b}");
+                }
+                if let Some(product) = a.checked_mul(b) {
+                    assert_eq!(big(product), &big(a) * &big(b), "{a} * {b}");
+                }
+                if b != 0 {
+                    assert_eq!(
+                        (big(a / b), big(a % b)),
+                        big(a).div_rem(&big(b)),
+                        "{a} / {b}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn beyond_u128() {
+        let factorial: BigInt = (1..=30_u32).map(BigInt::from).product();
+        assert_eq!("265252859812191058636308480000000", factorial.to_string());
+
+        let two_hundred = BigInt::from(2).pow(200);
+        assert_eq!(
+            "1606938044258990275541962092341162602522202993782792835301376",
+            two_hundred.to_string()
+        );
+        assert_eq!(None, two_hundred.to_i128());
+
+        let (quotient, remainder) = two_hundred.div_rem(&factorial);
+        assert_eq!(two_hundred, &quotient * &factorial + &remainder);
+        assert!(remainder < factorial);
+
+        let mut n: BigInt = "-000123456789012345678901234567890".parse().unwrap();
+        n += BigInt::from(10);
+        n *= BigInt::from(-1);
+        assert_eq!("123456789012345678901234567880", n.to_string());
+        assert_eq!(BigInt::zero(), -BigInt::zero());
+        assert!(!(-BigInt::zero()).is_negative());
+    }
+
+    #[test]
+    fn parse_errors() {
+        for bad in ["", "-", "12a", "1.5", " 1"] {
+            assert_eq!(Err(ParseBigIntError), bad.parse::<BigInt>(), "{bad:?}");
+        }
+    }
+}
@@ -0,0 +1,535 @@
+//! Boolean sets and grids packed into `u64` words, so whole rows get combined 64 cells at a time.
+
+use crate::grid::{Cells, Pos};
+
+/// Moves every bit `n` places towards the higher indices. Bits shifted past the end are lost.
+fn shift_words_up(words: &mut [u64], n: usize) {
+    let (word_shift, bit_shift) = (n / 64, n % 64);
+    for i in (0..words.len()).rev() {
+        let source = i.checked_sub(word_shift);
+        let high = source.map_or(0, |s| words[s] << bit_shift);
+        let low = match source.and_then(|s| s.checked_sub(1)) {
+            Some(s) if bit_shift > 0 => words[s] >> (64 - bit_shift),
+            _ => 0,
+        };
+        words[i] = high | low;
+    }
+}
+
+/// Moves every bit `n` places towards the lower indices.
+fn shift_words_down(words: &mut [u64], n: usize) {
+    let (word_shift, bit_shift) = (n / 64, n % 64);
+    for i in 0..words.len() {
+        let source = i + word_shift;
+        let low = words.get(source).map_or(0, |w| w >> bit_shift);
+        let high = match words.get(source + 1) {
+            Some(w) if bit_shift > 0 => w << (64 - bit_shift),
+            _ => 0,
+        };
+        words[i] = low | high;
+    }
+}
+
+/// Clears the bits at `len` and beyond, which only ever live in the last word.
+fn mask_tail(words: &mut [u64], len: usize) {
+    if let Some(last) = words.last_mut()
+        && !len.is_multiple_of(64)
+    {
+        *last &= (1 << (len % 64)) - 1;
+    }
+}
+
+/// Indices of the set bits, lowest first.
+fn ones(words: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    words.iter().enumerate().flat_map(|(i, word)| {
+        let mut word = *word;
+        std::iter::from_fn(move || {
+            if word == 0 {
+                return None;
+            }
+            let bit = word.trailing_zeros() as usize;
+            word &= word - 1;
+            Some(i * 64 + bit)
+        })
+    })
+}
+
+/// A set of integers in `0..capacity`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl BitSet {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(64)],
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// How many values are in the set.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < self.capacity && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    /// Returns whether the value was newly added.
+    ///
+    /// # Panics
+    ///
+    /// When `value` is outside the capacity.
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(
+            value < self.capacity,
+            "{value} doesn't fit in {}",
+            self.capacity
+        );
+        let added = !self.contains(value);
+        self.words[value / 64] |= 1 << (value % 64);
+        added
+    }
+
+    /// Returns whether the value was there.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let removed = self.contains(value);
+        if removed {
+            self.words[value / 64] &= !(1 << (value % 64));
+        }
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        ones(&self.words)
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a |= b);
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.words
+            .iter_mut()
+            .zip(other.words.iter().chain(std::iter::repeat(&0)))
+            .for_each(|(a, b)| *a &= b);
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a &= !b);
+    }
+
+    /// Every value `v` becomes `v + n`, dropping anything that no longer fits.
+    pub fn shift_up(&mut self, n: usize) {
+        shift_words_up(&mut self.words, n);
+        mask_tail(&mut self.words, self.capacity);
+    }
+
+    /// Every value `v` becomes `v - n`, dropping anything below zero.
+    pub fn shift_down(&mut self, n: usize) {
+        shift_words_down(&mut self.words, n);
+    }
+}
+
+/// A rectangle of on/off cells, one bit each, rows padded to whole words.
+///
+/// Reading outside the grid gives `false`, so neighbour lookups don't need bounds checks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+        Self {
+            words: vec![0; stride * height],
+            width,
+            height,
+            stride,
+        }
+    }
+
+    /// Sets the cells whose byte passes `is_set`. Shorter lines leave the rest of the row off.
+    pub fn from_lines<'a, I>(lines: I, is_set: impl Fn(u8) -> bool) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let lines: Vec<&str> = lines.into_iter().collect();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let mut grid = Self::new(width, lines.len());
+        for (row, line) in lines.iter().enumerate() {
+            for (col, b) in line.bytes().enumerate() {
+                if is_set(b) {
+                    grid.set(row, col, true);
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        row < self.height
+            && col < self.width
+            && self.words[row * self.stride + col / 64] & (1 << (col % 64)) != 0
+    }
+
+    /// # Panics
+    ///
+    /// When the cell is outside the grid.
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        assert!(
+            row < self.height && col < self.width,
+            "({row}, {col}) is outside a {}x{} grid",
+            self.width,
+            self.height
+        );
+        let word = &mut self.words[row * self.stride + col / 64];
+        if value {
+            *word |= 1 << (col % 64);
+        } else {
+            *word &= !(1 << (col % 64));
+        }
+    }
+
+    /// The packed words of one row, column 0 in the lowest bit of the first word.
+    pub fn row_words(&self, row: usize) -> &[u64] {
+        &self.words[row * self.stride..(row + 1) * self.stride]
+    }
+
+    /// How many cells are on.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// The `(row, col)` of every cell that's on, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height).flat_map(move |row| ones(self.row_words(row)).map(move |col| (row, col)))
+    }
+
+    fn zip_with(&self, other: &Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "grid sizes differ"
+        );
+        Self {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| f(*a, *b))
+                .collect(),
+            ..*self
+        }
+    }
+
+    fn mask(&mut self) {
+        if self.stride > 0 {
+            for row in self.words.chunks_mut(self.stride) {
+                mask_tail(row, self.width);
+            }
+        }
+    }
+
+    pub fn and(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn or(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn xor(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+
+    /// Cells on in `self` but not in `other`.
+    pub fn and_not(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    pub fn not(&self) -> Self {
+        let mut flipped = Self {
+            words: self.words.iter().map(|w| !w).collect(),
+            ..*self
+        };
+        flipped.mask();
+        flipped
+    }
+
+    /// Every cell moves `n` columns to the right; whatever falls off the edge is gone.
+    pub fn shift_right(&self, n: usize) -> Self {
+        let mut shifted = self.clone();
+        if self.stride > 0 {
+            for row in shifted.words.chunks_mut(self.stride) {
+                shift_words_up(row, n);
+            }
+        }
+        shifted.mask();
+        shifted
+    }
+
+    pub fn shift_left(&self, n: usize) -> Self {
+        let mut shifted = self.clone();
+        if self.stride > 0 {
+            for row in shifted.words.chunks_mut(self.stride) {
+                shift_words_down(row, n);
+            }
+        }
+        shifted
+    }
+
+    /// Every cell moves `n` rows down.
+    pub fn shift_down(&self, n: usize) -> Self {
+        let mut shifted = Self::new(self.width, self.height);
+        let kept = self.height.saturating_sub(n) * self.stride;
+        shifted.words[self.words.len() - kept..].copy_from_slice(&self.words[..kept]);
+        shifted
+    }
+
+    pub fn shift_up(&self, n: usize) -> Self {
+        let mut shifted = Self::new(self.width, self.height);
+        let kept = self.height.saturating_sub(n) * self.stride;
+        shifted.words[..kept].copy_from_slice(&self.words[self.words.len() - kept..]);
+        shifted
+    }
+
+    /// For every cell, how many of its eight neighbours are on.
+    ///
+    /// The eight shifted copies of the grid are added together as 4-bit numbers spread over four
+    /// planes, so it's all whole-word operations.
+    pub fn neighbour_counts(&self) -> NeighbourCounts {
+        let above = self.shift_down(1);
+        let below = self.shift_up(1);
+        let neighbours = [
+            above.shift_right(1),
+            above.clone(),
+            above.shift_left(1),
+            self.shift_right(1),
+            self.shift_left(1),
+            below.shift_right(1),
+            below.clone(),
+            below.shift_left(1),
+        ];
+
+        let mut planes: [BitGrid; 4] = std::array::from_fn(|_| Self::new(self.width, self.height));
+        for neighbour in neighbours {
+            let mut carry = neighbour;
+            for plane in planes.iter_mut() {
+                let sum = plane.xor(&carry);
+                carry = plane.and(&carry);
+                *plane = sum;
+            }
+        }
+
+        NeighbourCounts { planes }
+    }
+}
+
+impl Cells for BitGrid {
+    type Cell = bool;
+
+    fn locate(&self, pos: Pos) -> Option<Pos> {
+        let inside =
+            (0..self.height as isize).contains(&pos.0) && (0..self.width as isize).contains(&pos.1);
+        inside.then_some(pos)
+    }
+
+    fn cell(&self, pos: Pos) -> Option<bool> {
+        let (row, col) = self.locate(pos)?;
+        Some(self.get(row as usize, col as usize))
+    }
+
+    /// # Panics
+    ///
+    /// When the cell is outside the grid.
+    fn set_cell(&mut self, (row, col): Pos, value: bool) {
+        assert!(
+            row >= 0 && col >= 0,
+            "({row}, {col}) is outside a {}x{} grid",
+            self.width,
+            self.height
+        );
+        self.set(row as usize, col as usize, value);
+    }
+}
+
+/// Per-cell neighbour counts from [`BitGrid::neighbour_counts`], stored as bit planes: plane `k`
+/// holds bit `k` of every cell's count.
+#[derive(Debug, Clone)]
+pub struct NeighbourCounts {
+    planes: [BitGrid; 4],
+}
+
+impl NeighbourCounts {
+    pub fn get(&self, row: usize, col: usize) -> u8 {
+        self.planes
+            .iter()
+            .enumerate()
+            .map(|(k, plane)| (plane.get(row, col) as u8) << k)
+            .sum()
+    }
+
+    /// The cells with exactly `n` neighbours on.
+    pub fn exactly(&self, n: u8) -> BitGrid {
+        let [first, ..] = &self.planes;
+        let all = BitGrid::new(first.width, first.height).not();
+        self.planes.iter().enumerate().fold(all, |acc, (k, plane)| {
+            if n & (1 << k) != 0 {
+                acc.and(plane)
+            } else {
+                acc.and_not(plane)
+            }
+        })
+    }
+
+    /// The cells with fewer than `n` neighbours on.
+    pub fn fewer_than(&self, n: u8) -> BitGrid {
+        let [first, ..] = &self.planes;
+        (0..n).fold(BitGrid::new(first.width, first.height), |acc, count| {
+            acc.or(&self.exactly(count))
+        })
+    }
+
+    pub fn at_least(&self, n: u8) -> BitGrid {
+        self.fewer_than(n).not()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::SURROUNDING;
+
+    #[test]
+    fn sample() {
+        // 2025 day 4
+        let lines = [
+            "..@@.@@@@.",
+            "@@@.@.@.@@",
+            "@@@@@.@.@@",
+            "@.@@@@..@.",
+            "@@.@@@@.@@",
+            ".@@@@@@@.@",
+            ".@.@.@.@@@",
+            "@.@@@.@@@@",
+            ".@@@@@@@@.",
+            "@.@.@@@.@.",
+        ];
+        let grid = BitGrid::from_lines(lines, |b| b == b'@');
+        let counts = grid.neighbour_counts();
+
+        for row in 0..grid.height() {
+            for col in 0..grid.width() {
+                let naive = (-1..=1_isize)
+                    .flat_map(|dr| (-1..=1_isize).map(move |dc| (dr, dc)))
+                    .filter(|d| *d != (0, 0))
+                    .filter(|(dr, dc)| {
+                        let (r, c) = (row as isize + dr, col as isize + dc);
+                        r >= 0 && c >= 0 && grid.get(r as usize, c as usize)
+                    })
+                    .count();
+                assert_eq!(naive as u8, counts.get(row, col), "({row}, {col})");
+            }
+        }
+
+        assert_eq!(13, counts.fewer_than(4).and(&grid).count());
+        assert_eq!(counts.fewer_than(4), counts.at_least(4).not());
+        assert_eq!(2, counts.get(0, 0));
+        assert_eq!(7, counts.get(2, 1));
+
+        let around = |pos| {
+            grid.neighbours(pos, &SURROUNDING)
+                .filter(|p| grid.cell(*p) == Some(true))
+                .count()
+        };
+        assert_eq!(2, around((0, 0)));
+        assert_eq!(7, around((2, 1)));
+        assert_eq!(None, grid.cell((10, 0)));
+    }
+
+    #[test]
+    fn shifts() {
+        let mut grid = BitGrid::new(130, 3);
+        grid.set(1, 63, true);
+        grid.set(1, 129, true);
+        grid.set(2, 0, true);
+
+        let right = grid.shift_right(1);
+        assert_eq!(vec![(1, 64), (2, 1)], right.iter().collect::<Vec<_>>());
+        let left = grid.shift_left(64);
+        assert_eq!(vec![(1, 65)], left.iter().collect::<Vec<_>>());
+        let down = grid.shift_down(1);
+        assert_eq!(vec![(2, 63), (2, 129)], down.iter().collect::<Vec<_>>());
+        assert_eq!(
+            vec![(0, 63), (0, 129), (1, 0)],
+            grid.shift_up(1).iter().collect::<Vec<_>>()
+        );
+
+        assert_eq!(130 * 3 - 3, grid.not().count());
+        assert!(!grid.get(1, 130));
+        assert!(!grid.get(3, 0));
+        assert_eq!(grid, grid.or(&right).and_not(&right.and_not(&grid)));
+    }
+
+    #[test]
+    fn set() {
+        let mut set = BitSet::new(200);
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        set.insert(64);
+        set.insert(199);
+        assert_eq!(3, set.count());
+        assert_eq!(vec![3, 64, 199], set.iter().collect::<Vec<_>>());
+
+        let mut shifted = set.clone();
+        shifted.shift_up(1);
+        assert_eq!(vec![4, 65], shifted.iter().collect::<Vec<_>>());
+        shifted.shift_down(5);
+        assert_eq!(vec![60], shifted.iter().collect::<Vec<_>>());
+
+        let mut other = BitSet::new(200);
+        other.insert(64);
+        other.insert(100);
+        let mut union = set.clone();
+        union.union_with(&other);
+        assert_eq!(4, union.count());
+        union.intersect_with(&other);
+        assert_eq!(vec![64, 100], union.iter().collect::<Vec<_>>());
+        set.difference_with(&other);
+        assert_eq!(vec![3, 199], set.iter().collect::<Vec<_>>());
+
+        assert!(set.remove(3));
+        assert!(!set.contains(3));
+        assert!(!set.contains(1000));
+    }
+}
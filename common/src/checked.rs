@@ -0,0 +1,268 @@
+//! Integers that refuse to wrap.
+//!
+//! Release builds wrap on overflow without a word, which turns into a wrong answer instead of a
+//! crash. [`Checked`] does every operation with the `checked_*` methods and panics with the day,
+//! part and operation that overflowed. For everything else there's the `checked` profile in each
+//! year's `Cargo.toml`, which is a release build with overflow checks turned back on:
+//!
+//! ```text
+//! cargo run --profile checked
+//! ```
+
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign},
+};
+
+/// The primitive integers, as far as [`Checked`] cares.
+pub trait Int: Copy + Display {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn try_add(self, rhs: Self) -> Option<Self>;
+    fn try_sub(self, rhs: Self) -> Option<Self>;
+    fn try_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! int {
+    ($($t:ty),*) => {$(
+        impl Int for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn try_add(self, rhs: Self) -> Option<Self> {
+                self.checked_add(rhs)
+            }
+
+            fn try_sub(self, rhs: Self) -> Option<Self> {
+                self.checked_sub(rhs)
+            }
+
+            fn try_mul(self, rhs: Self) -> Option<Self> {
+                self.checked_mul(rhs)
+            }
+        }
+    )*};
+}
+
+int!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// Which solution a number belongs to, for the overflow report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Context {
+    pub day: u8,
+    pub part: Option<u8>,
+}
+
+impl Context {
+    /// For helpers shared by both parts of a day.
+    pub const fn day(day: u8) -> Self {
+        Self { day, part: None }
+    }
+
+    pub const fn part(self, part: u8) -> Self {
+        Self {
+            part: Some(part),
+            ..self
+        }
+    }
+
+    pub fn wrap<T: Int>(self, value: T) -> Checked<T> {
+        Checked {
+            value,
+            context: self,
+        }
+    }
+
+    pub fn sum<T: Int>(self, values: impl IntoIterator<Item = T>) -> T {
+        values
+            .into_iter()
+            .fold(self.wrap(T::ZERO), |acc, x| acc + x)
+            .get()
+    }
+
+    pub fn product<T: Int>(self, values: impl IntoIterator<Item = T>) -> T {
+        values
+            .into_iter()
+            .fold(self.wrap(T::ONE), |acc, x| acc * x)
+            .get()
+    }
+}
+
+impl Display for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.part {
+            Some(part) => write!(f, "day {} part {part}", self.day),
+            None => write!(f, "day {}", self.day),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Sub,
+    Mul,
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operation::Add => "+",
+            Operation::Sub => "-",
+            Operation::Mul => "*",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+/// What went wrong, with the operands already formatted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow {
+    pub context: Context,
+    pub operation: Operation,
+    pub lhs: String,
+    pub rhs: String,
+    pub type_name: &'static str,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} overflowed: {} {} {} doesn't fit in {}",
+            self.context, self.lhs, self.operation, self.rhs, self.type_name
+        )
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// An integer that panics with an [`Overflow`] report instead of wrapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checked<T> {
+    value: T,
+    context: Context,
+}
+
+impl<T: Int> Checked<T> {
+    pub fn get(self) -> T {
+        self.value
+    }
+
+    fn apply(
+        self,
+        operation: Operation,
+        rhs: T,
+        f: fn(T, T) -> Option<T>,
+    ) -> Result<Self, Overflow> {
+        match f(self.value, rhs) {
+            Some(value) => Ok(Self { value, ..self }),
+            None => Err(Overflow {
+                context: self.context,
+                operation,
+                lhs: self.value.to_string(),
+                rhs: rhs.to_string(),
+                type_name: std::any::type_name::<T>(),
+            }),
+        }
+    }
+
+    pub fn try_add(self, rhs: T) -> Result<Self, Overflow> {
+        self.apply(Operation::Add, rhs, T::try_add)
+    }
+
+    pub fn try_sub(self, rhs: T) -> Result<Self, Overflow> {
+        self.apply(Operation::Sub, rhs, T::try_sub)
+    }
+
+    pub fn try_mul(self, rhs: T) -> Result<Self, Overflow> {
+        self.apply(Operation::Mul, rhs, T::try_mul)
+    }
+}
+
+impl<T: Display> Display for Checked<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+macro_rules! ops {
+    ($($trait:ident $method:ident $assign_trait:ident $assign_method:ident $try:ident),*) => {$(
+        impl<T: Int> $trait<T> for Checked<T> {
+            type Output = Checked<T>;
+
+            fn $method(self, rhs: T) -> Checked<T> {
+                self.$try(rhs).unwrap_or_else(|overflow| panic!("{overflow}"))
+            }
+        }
+
+        impl<T: Int> $trait for Checked<T> {
+            type Output = Checked<T>;
+
+            fn $method(self, rhs: Checked<T>) -> Checked<T> {
+                self.$method(rhs.value)
+            }
+        }
+
+        impl<T: Int> $assign_trait<T> for Checked<T> {
+            fn $assign_method(&mut self, rhs: T) {
+                *self = self.$method(rhs);
+            }
+        }
+
+        impl<T: Int> $assign_trait for Checked<T> {
+            fn $assign_method(&mut self, rhs: Checked<T>) {
+                *self = self.$method(rhs.value);
+            }
+        }
+    )*};
+}
+
+ops!(
+    Add add AddAssign add_assign try_add,
+    Sub sub SubAssign sub_assign try_sub,
+    Mul mul MulAssign mul_assign try_mul
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Context = Context::day(5);
+
+    #[test]
+    fn sample() {
+        assert_eq!(143_u16, DAY.part(1).sum([61, 53, 29]));
+        assert_eq!(120_u32, DAY.product(1..=5));
+
+        let mut total = DAY.part(2).wrap(10_i32);
+        total += 5;
+        total -= DAY.wrap(20);
+        total *= -3;
+        assert_eq!(15, total.get());
+
+        let overflow = DAY.part(1).wrap(65000_u16).try_add(600).unwrap_err();
+        assert_eq!(Operation::Add, overflow.operation);
+        assert_eq!(
+            "day 5 part 1 overflowed: 65000 + 600 doesn't fit in u16",
+            overflow.to_string()
+        );
+        assert!(DAY.wrap(0_u64).try_sub(1).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "day 9 overflowed: 4294967296 * 4294967296 doesn't fit in u64")]
+    fn panics_with_report() {
+        let side = 1_u64 << 32;
+        let _ = Context::day(9).wrap(side) * side;
+    }
+
+    #[test]
+    #[should_panic(expected = "day 3 part 1 overflowed")]
+    fn sum_panics() {
+        Context::day(3).part(1).sum([i32::MAX, 1]);
+    }
+}
@@ -0,0 +1,170 @@
+//! Coordinate compression: squash a handful of huge, sparse coordinates into a small dense grid
+//! while remembering how much real space every cell stands for.
+
+use crate::{grid::Grid, point::Point2};
+
+/// One axis. Every distinct value gets its own cell; with gaps on, the stretch strictly between
+/// two neighbouring values gets a cell too, so "between" stays representable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Axis {
+    values: Vec<i64>,
+    gaps: bool,
+}
+
+impl Axis {
+    pub fn new(values: impl IntoIterator<Item = i64>, gaps: bool) -> Self {
+        let mut values: Vec<i64> = values.into_iter().collect();
+        values.sort_unstable();
+        values.dedup();
+        Self { values, gaps }
+    }
+
+    /// How many cells there are.
+    pub fn len(&self) -> usize {
+        match (self.values.len(), self.gaps) {
+            (0, _) => 0,
+            (n, true) => 2 * n - 1,
+            (n, false) => n,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The cell holding one of the original values.
+    pub fn index(&self, value: i64) -> Option<usize> {
+        let i = self.values.binary_search(&value).ok()?;
+        Some(if self.gaps { i * 2 } else { i })
+    }
+
+    /// The real values a cell covers, inclusive. Gap cells between adjacent values are empty,
+    /// with `start > end`.
+    pub fn span(&self, cell: usize) -> (i64, i64) {
+        if !self.gaps {
+            return (self.values[cell], self.values[cell]);
+        }
+        let i = cell / 2;
+        if cell.is_multiple_of(2) {
+            (self.values[i], self.values[i])
+        } else {
+            (self.values[i] + 1, self.values[i + 1] - 1)
+        }
+    }
+
+    /// How many real values a cell covers.
+    pub fn width(&self, cell: usize) -> i64 {
+        let (start, end) = self.span(cell);
+        (end - start + 1).max(0)
+    }
+}
+
+/// Both axes of a set of points, with cells indexed `(row, col)` like [`Grid`]: rows follow `y`,
+/// columns follow `x`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compressed {
+    pub xs: Axis,
+    pub ys: Axis,
+}
+
+impl Compressed {
+    pub fn new<'a, I>(points: I, gaps: bool) -> Self
+    where
+        I: IntoIterator<Item = &'a Point2<i64>>,
+    {
+        let (xs, ys): (Vec<i64>, Vec<i64>) = points.into_iter().map(|p| (p.x, p.y)).unzip();
+        Self {
+            xs: Axis::new(xs, gaps),
+            ys: Axis::new(ys, gaps),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.ys.len()
+    }
+
+    /// The `(row, col)` of one of the original points.
+    pub fn cell(&self, point: &Point2<i64>) -> Option<(usize, usize)> {
+        Some((self.ys.index(point.y)?, self.xs.index(point.x)?))
+    }
+
+    /// How much real area each cell stands for.
+    pub fn weights(&self) -> Grid<i64> {
+        Grid::from_fn(self.width(), self.height(), |row, col| {
+            self.ys.width(row) * self.xs.width(col)
+        })
+    }
+
+    /// Maps a compressed answer back to the real world: the total area of the cells marked `true`.
+    pub fn area(&self, marked: &Grid<bool>) -> i64 {
+        marked
+            .iter()
+            .filter(|(_, on)| **on)
+            .map(|((row, col), _)| self.ys.width(row) * self.xs.width(col))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prefix::PrefixSum2D;
+
+    #[test]
+    fn sample() {
+        // 2025 day 9's red tiles
+        let points: Vec<Point2<i64>> = [
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ]
+        .into_iter()
+        .map(|(x, y)| Point2::new(x, y))
+        .collect();
+
+        let tight = Compressed::new(&points, false);
+        assert_eq!((4, 4), (tight.width(), tight.height()));
+        assert_eq!(Some((0, 1)), tight.cell(&Point2::new(7, 1)));
+
+        let compressed = Compressed::new(&points, true);
+        assert_eq!((7, 7), (compressed.width(), compressed.height()));
+        assert_eq!(Some((6, 4)), compressed.cell(&Point2::new(9, 7)));
+        assert_eq!(None, compressed.cell(&Point2::new(8, 7)));
+        assert_eq!((3, 6), compressed.xs.span(1));
+        assert_eq!(4, compressed.xs.width(1));
+        assert_eq!(1, compressed.xs.width(2));
+
+        // the weights add back up to the bounding box, 10 x 7
+        let weights = compressed.weights();
+        let sums = PrefixSum2D::new(&weights);
+        assert_eq!(70, sums.sum(0..7, 0..7));
+        // and any rectangle between red tiles comes out at its real size: 9,5 to 2,3 is 8 x 3
+        let (a, b) = (Point2::new(9, 5), Point2::new(2, 3));
+        let (a, b) = (compressed.cell(&a).unwrap(), compressed.cell(&b).unwrap());
+        assert_eq!(24, sums.sum_inclusive(a, b));
+
+        let mut marked = Grid::new(7, 7, false);
+        marked[(0, 0)] = true;
+        marked[(1, 1)] = true;
+        assert_eq!(1 + 4, compressed.area(&marked));
+    }
+
+    #[test]
+    fn adjacent() {
+        let axis = Axis::new([5, 3, 4, 3], true);
+        assert_eq!(5, axis.len());
+        assert_eq!(Some(4), axis.index(5));
+        assert_eq!(0, axis.width(1));
+        assert!(Axis::new([], true).is_empty());
+        assert_eq!(0, Axis::new([], true).len());
+    }
+}
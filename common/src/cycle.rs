@@ -0,0 +1,219 @@
+use std::hash::Hash;
+
+use crate::hash::FastMap;
+
+/// Where a deterministic sequence of states starts repeating, and how long each loop is.
+///
+/// State `start` is the first state that is seen again, and `period` steps later it comes back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Maps step `n` onto the earliest step that has the same state.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Every state seen before the sequence started repeating, along with the cycle found.
+#[derive(Debug)]
+pub struct History<T> {
+    pub states: Vec<T>,
+    pub cycle: Cycle,
+}
+
+impl<T> History<T> {
+    /// The state after `n` steps, without simulating them.
+    pub fn nth(&self, n: usize) -> &T {
+        &self.states[self.cycle.reduce(n)]
+    }
+}
+
+/// Finds the cycle using Brent's algorithm. Only a couple of states are kept around at a time,
+/// so this is the one to use when states are big.
+///
+/// Never returns if the sequence doesn't repeat.
+pub fn brent<T, F>(initial: &T, mut step: F) -> Cycle
+where
+    T: Clone + Eq,
+    F: FnMut(&T) -> T,
+{
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// Finds the cycle by remembering every state in a hash map. Each state is only stepped once,
+/// which is cheaper than [`brent`] when stepping is the expensive part.
+///
+/// Never returns if the sequence doesn't repeat.
+pub fn find_cycle<T, F>(initial: T, mut step: F) -> History<T>
+where
+    T: Clone + Hash + Eq,
+    F: FnMut(&T) -> T,
+{
+    let mut detector = Detector::default();
+    let mut states: Vec<T> = vec![];
+    let mut state = initial;
+
+    loop {
+        if let Some(cycle) = detector.visit(&state) {
+            return History { states, cycle };
+        }
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// The bookkeeping behind [`find_cycle`], for when something else is doing the stepping. Hand it
+/// every state in order, starting with the initial one.
+#[derive(Debug)]
+pub struct Detector<T> {
+    seen: FastMap<T, usize>,
+}
+
+impl<T> Default for Detector<T> {
+    fn default() -> Self {
+        Self {
+            seen: FastMap::default(),
+        }
+    }
+}
+
+impl<T: Clone + Hash + Eq> Detector<T> {
+    /// The cycle, if `state` has been visited before. Otherwise it's remembered as the next step.
+    pub fn visit(&mut self, state: &T) -> Option<Cycle> {
+        let steps = self.seen.len();
+        if let Some(&start) = self.seen.get(state) {
+            return Some(Cycle {
+                start,
+                period: steps - start,
+            });
+        }
+        self.seen.insert(state.clone(), steps);
+        None
+    }
+}
+
+/// The state after `n` steps. Only simulates up to the first repeat and then the leftover
+/// steps, so `n` can be huge.
+pub fn nth_state<T, F>(initial: T, mut step: F, n: usize) -> T
+where
+    T: Clone + Eq,
+    F: FnMut(&T) -> T,
+{
+    let cycle = brent(&initial, &mut step);
+    (0..cycle.reduce(n)).fold(initial, |state, _| step(&state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulate<T, F: FnMut(&T) -> T>(initial: T, mut step: F, n: usize) -> T {
+        (0..n).fold(initial, |state, _| step(&state))
+    }
+
+    #[test]
+    fn sample() {
+        // 0 -> 1 -> 2 -> 5 -> 26 -> 677 -> 330 -> ...
+        let step = |x: &u64| (x * x + 1) % 1000;
+
+        let history = find_cycle(0_u64, step);
+        let cycle = brent(&0_u64, step);
+        assert_eq!(history.cycle, cycle);
+        assert_eq!(
+            history.states[cycle.start],
+            simulate(0, step, cycle.start + cycle.period)
+        );
+
+        for n in [0, 1, 5, 17, 100, 12345] {
+            let expected = simulate(0, step, n);
+            assert_eq!(expected, *history.nth(n));
+            assert_eq!(expected, nth_state(0, step, n));
+        }
+    }
+
+    #[test]
+    fn pure_loop() {
+        // a robot walking around a 7-wide torus, 3 tiles at a time
+        let step = |x: &i32| (x + 3).rem_euclid(7);
+        let cycle = brent(&0, step);
+        assert_eq!(
+            Cycle {
+                start: 0,
+                period: 7
+            },
+            cycle
+        );
+        assert_eq!(cycle, find_cycle(0, step).cycle);
+        assert_eq!(
+            3 * (1_000_000_007 % 7) % 7,
+            nth_state(0, step, 1_000_000_007)
+        );
+    }
+
+    #[test]
+    fn fixed_point() {
+        let step = |x: &u32| x.saturating_sub(4);
+        let history = find_cycle(10, step);
+        assert_eq!(
+            Cycle {
+                start: 3,
+                period: 1
+            },
+            history.cycle
+        );
+        assert_eq!(vec![10, 6, 2, 0], history.states);
+        assert_eq!(0, *history.nth(usize::MAX));
+        assert_eq!(history.cycle, brent(&10, step));
+    }
+
+    #[test]
+    fn detector() {
+        let mut detector = Detector::default();
+        for state in ['a', 'b', 'c', 'd'] {
+            assert_eq!(None, detector.visit(&state));
+        }
+        assert_eq!(
+            Some(Cycle {
+                start: 1,
+                period: 3
+            }),
+            detector.visit(&'b')
+        );
+    }
+}
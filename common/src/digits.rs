@@ -0,0 +1,200 @@
+//! Base 10 digit tricks done with integer arithmetic instead of `to_string()`.
+
+/// `10^exp`.
+pub fn pow10(exp: u32) -> u64 {
+    10_u64.pow(exp)
+}
+
+/// How many digits `n` has in `base`. Zero has one digit. Panics if `base` is below 2.
+pub fn count_in(mut n: u64, base: u64) -> u32 {
+    assert!(base >= 2, "there are no digits in base {base}");
+    let mut count = 1;
+    while n >= base {
+        n /= base;
+        count += 1;
+    }
+    count
+}
+
+/// How many decimal digits `n` has. Zero has one digit.
+pub fn count(n: u64) -> u32 {
+    n.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// Splits off the lowest `low_digits` digits: `split(123456, 2) == (1234, 56)`.
+pub fn split(n: u64, low_digits: u32) -> (u64, u64) {
+    let divisor = pow10(low_digits);
+    (n / divisor, n % divisor)
+}
+
+/// Splits `n` into its first and second half, if it has an even number of digits.
+pub fn split_half(n: u64) -> Option<(u64, u64)> {
+    let count = count(n);
+    count.is_multiple_of(2).then(|| split(n, count / 2))
+}
+
+/// Writes `right` after `left`: `concat(12, 345) == 12345`. Panics if that doesn't fit in a
+/// `u64`.
+pub fn concat(left: u64, right: u64) -> u64 {
+    checked_concat(left, right).unwrap_or_else(|| panic!("{left}{right} doesn't fit in u64"))
+}
+
+/// Like [`concat`], but `None` instead of overflowing.
+pub fn checked_concat(left: u64, right: u64) -> Option<u64> {
+    // the shift alone overflows when `right` has 20 digits, even if `left` is 0
+    let shift = 10_u64.checked_pow(count(right))?;
+    left.checked_mul(shift)?.checked_add(right)
+}
+
+/// The digits of `n` in `base`, most significant first. Panics if `base` is below 2.
+pub fn iter(n: u64, base: u64) -> impl Iterator<Item = u64> {
+    assert!(base >= 2, "there are no digits in base {base}");
+    let mut divisor = base.pow(count_in(n, base) - 1);
+    std::iter::from_fn(move || {
+        if divisor == 0 {
+            return None;
+        }
+        let digit = n / divisor % base;
+        divisor /= base;
+        Some(digit)
+    })
+}
+
+/// The digits of `n` backwards. Trailing zeros disappear: `reverse(1200) == 21`.
+pub fn reverse(mut n: u64) -> u64 {
+    let mut reversed = 0;
+    while n > 0 {
+        reversed = reversed * 10 + n % 10;
+        n /= 10;
+    }
+    reversed
+}
+
+/// Whether `n` is a block of `block_len` digits repeated at least twice, like `1212` with a block
+/// of 2 or `777` with a block of 1.
+pub fn repeats(n: u64, block_len: u32) -> bool {
+    let count = count(n);
+    if block_len == 0 || block_len == count || !count.is_multiple_of(block_len) {
+        return false;
+    }
+
+    let modulus = pow10(block_len);
+    let block = n % modulus;
+    let mut rest = n;
+    while rest > 0 {
+        if rest % modulus != block {
+            return false;
+        }
+        rest /= modulus;
+    }
+
+    true
+}
+
+/// Whether `n` is some block of digits repeated at least twice.
+pub fn is_repeated(n: u64) -> bool {
+    (1..=count(n) / 2).any(|block_len| repeats(n, block_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NUMBERS: [u64; 14] = [
+        0,
+        1,
+        9,
+        10,
+        99,
+        100,
+        1010,
+        1200,
+        222222,
+        446446,
+        1188511885,
+        2121212118,
+        824824824,
+        u64::MAX,
+    ];
+
+    #[test]
+    fn sample() {
+        for n in NUMBERS {
+            let string = n.to_string();
+            assert_eq!(string.len() as u32, count(n), "{n}");
+            assert_eq!(string.len() as u32, count_in(n, 10), "{n}");
+            assert_eq!(format!("{n:b}").len() as u32, count_in(n, 2), "{n}");
+
+            let digits: Vec<u64> = string.bytes().map(|b| (b - b'0') as u64).collect();
+            assert_eq!(digits, iter(n, 10).collect::<Vec<_>>(), "{n}");
+            let hex: Vec<u64> = format!("{n:x}")
+                .chars()
+                .map(|c| c.to_digit(16).unwrap() as u64)
+                .collect();
+            assert_eq!(hex, iter(n, 16).collect::<Vec<_>>(), "{n}");
+
+            if n != u64::MAX {
+                let reversed: String = string.chars().rev().collect();
+                assert_eq!(reversed.parse::<u64>().unwrap(), reverse(n), "{n}");
+            }
+
+            let halves = (string.len() % 2 == 0).then(|| {
+                let (first, second) = string.split_at(string.len() / 2);
+                (first.parse().unwrap(), second.parse().unwrap())
+            });
+            assert_eq!(halves, split_half(n), "{n}");
+
+            if string.len() % 2 == 0 {
+                let (first, second) = string.split_at(string.len() / 2);
+                assert_eq!(first == second, repeats(n, count(n) / 2), "{n}");
+            }
+        }
+    }
+
+    #[test]
+    fn concatenation() {
+        assert_eq!(12345, concat(12, 345));
+        assert_eq!(10, concat(1, 0));
+        assert_eq!(7, concat(0, 7));
+        for (a, b) in [(15, 6), (17, 8), (486, 6), (1, 23456789)] {
+            assert_eq!(format!("{a}{b}").parse::<u64>().unwrap(), concat(a, b));
+        }
+        assert_eq!(None, checked_concat(u64::MAX / 10, 99));
+        assert_eq!(Some(123), checked_concat(1, 23));
+        assert_eq!(None, checked_concat(0, u64::MAX));
+        assert_eq!(None, checked_concat(1, 10_u64.pow(19)));
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in u64")]
+    fn concat_overflow() {
+        concat(u64::MAX, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "no digits in base 1")]
+    fn base_one() {
+        count_in(5, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "no digits in base 0")]
+    fn base_zero() {
+        let _ = iter(5, 0);
+    }
+
+    #[test]
+    fn repetition() {
+        assert!(repeats(11, 1));
+        assert!(repeats(1188511885, 5));
+        assert!(repeats(824824824, 3));
+        assert!(!repeats(824824824, 9));
+        assert!(!repeats(1010, 3));
+        assert!(!repeats(5, 1));
+
+        let repeated: Vec<u64> = (95..=115).filter(|n| is_repeated(*n)).collect();
+        assert_eq!(vec![99, 111], repeated);
+        assert!(is_repeated(2121212121));
+        assert!(!is_repeated(2121212118));
+    }
+}
@@ -0,0 +1,412 @@
+//! Directed graphs over any hashable node type.
+
+use std::{fmt::Display, hash::Hash};
+
+use crate::hash::FastMap;
+
+/// A directed graph stored as adjacency lists. Nodes are numbered in the order they were first
+/// seen, and every traversal follows that order, so results are deterministic.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    index: FastMap<N, usize>,
+    edges: Vec<Vec<usize>>,
+}
+
+/// Returned by [`Graph::topological_sort`] when there's no valid order: one of the cycles in the
+/// way, with the first node repeated at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N> {
+    pub nodes: Vec<N>,
+}
+
+impl<N: Display> Display for Cycle<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path: Vec<String> = self.nodes.iter().map(|n| n.to_string()).collect();
+        write!(f, "cycle: {}", path.join(" -> "))
+    }
+}
+
+impl<N: Display + std::fmt::Debug> std::error::Error for Cycle<N> {}
+
+impl<N: Hash + Eq + Clone> Default for Graph<N> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            index: FastMap::default(),
+            edges: vec![],
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone> FromIterator<(N, N)> for Graph<N> {
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(edges: I) -> Self {
+        let mut graph = Self::default();
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+}
+
+impl<N: Hash + Eq + Clone> Graph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        edges.into_iter().collect()
+    }
+
+    /// Adds the node if it's new, and returns its number either way.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&i) = self.index.get(&node) {
+            return i;
+        }
+        let i = self.nodes.len();
+        self.index.insert(node.clone(), i);
+        self.nodes.push(node);
+        self.edges.push(vec![]);
+        i
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        if !self.edges[from].contains(&to) {
+            self.edges[from].push(to);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.index.contains_key(node)
+    }
+
+    pub fn neighbours(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.index
+            .get(node)
+            .into_iter()
+            .flat_map(|i| self.edges[*i].iter().map(|j| &self.nodes[*j]))
+    }
+
+    pub fn has_edge(&self, from: &N, to: &N) -> bool {
+        match (self.index.get(from), self.index.get(to)) {
+            (Some(from), Some(to)) => self.edges[*from].contains(to),
+            _ => false,
+        }
+    }
+
+    /// Every edge as `(from, to)`.
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N)> {
+        let nodes = &self.nodes;
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(move |(i, out)| out.iter().map(move |j| (&nodes[i], &nodes[*j])))
+    }
+
+    /// The graph restricted to `nodes` and the edges between them. Nodes the graph has never
+    /// heard of are still included, just without any edges.
+    pub fn subgraph<'a>(&self, nodes: impl IntoIterator<Item = &'a N>) -> Self
+    where
+        N: 'a,
+    {
+        let mut sub = Self::new();
+        let mut original = vec![];
+        for node in nodes {
+            let before = sub.len();
+            if sub.add_node(node.clone()) == before {
+                original.push(self.index.get(node).copied());
+            }
+        }
+
+        for (i, from) in original.iter().enumerate() {
+            let Some(from) = from else { continue };
+            for to in &self.edges[*from] {
+                if let Some(&j) = sub.index.get(&self.nodes[*to]) {
+                    sub.edges[i].push(j);
+                }
+            }
+        }
+        sub
+    }
+
+    /// Every node after all of its predecessors (Kahn's algorithm). Among nodes that are free at
+    /// the same time, the one added to the graph first goes first.
+    pub fn topological_sort(&self) -> Result<Vec<N>, Cycle<N>> {
+        let mut in_degree = vec![0; self.len()];
+        for out in &self.edges {
+            for j in out {
+                in_degree[*j] += 1;
+            }
+        }
+
+        let mut ready: std::collections::BTreeSet<usize> =
+            (0..self.len()).filter(|i| in_degree[*i] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(i) = ready.pop_first() {
+            order.push(self.nodes[i].clone());
+            for j in &self.edges[i] {
+                in_degree[*j] -= 1;
+                if in_degree[*j] == 0 {
+                    ready.insert(*j);
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle(&in_degree))
+        }
+    }
+
+    /// Everything Kahn couldn't place still has a predecessor that also wasn't placed, so walking
+    /// backwards from any of them has to come back around.
+    fn find_cycle(&self, in_degree: &[usize]) -> Cycle<N> {
+        let stuck = |i: usize| in_degree[i] > 0;
+        let mut predecessor = vec![None; self.len()];
+        for (i, out) in self.edges.iter().enumerate() {
+            for j in out {
+                if stuck(i) && stuck(*j) && predecessor[*j].is_none() {
+                    predecessor[*j] = Some(i);
+                }
+            }
+        }
+
+        let start = (0..self.len()).find(|i| stuck(*i)).unwrap();
+        let mut seen = vec![false; self.len()];
+        let mut path = vec![];
+        let mut current = start;
+        while !seen[current] {
+            seen[current] = true;
+            path.push(current);
+            current = predecessor[current].unwrap();
+        }
+
+        let from = path.iter().position(|i| *i == current).unwrap();
+        let mut cycle: Vec<usize> = path[from..].iter().rev().copied().collect();
+        // start from whichever node was added first, so the same graph always reports the same way
+        let first = (0..cycle.len()).min_by_key(|k| cycle[*k]).unwrap();
+        cycle.rotate_left(first);
+        let mut nodes: Vec<N> = cycle.iter().map(|i| self.nodes[*i].clone()).collect();
+        nodes.push(nodes[0].clone());
+        Cycle { nodes }
+    }
+
+    /// Tarjan's strongly connected components. Components come out in reverse topological order:
+    /// nothing in a component points at a component listed after it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        let mut tarjan = Tarjan {
+            edges: &self.edges,
+            next_index: 0,
+            index: vec![None; self.len()],
+            low_link: vec![0; self.len()],
+            on_stack: vec![false; self.len()],
+            stack: vec![],
+            components: vec![],
+        };
+        for i in 0..self.len() {
+            if tarjan.index[i].is_none() {
+                tarjan.visit(i);
+            }
+        }
+
+        tarjan
+            .components
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|i| self.nodes[i].clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Groups of nodes joined by edges in either direction, each in the order nodes were added.
+    pub fn connected_components(&self) -> Vec<Vec<N>> {
+        let mut undirected = vec![vec![]; self.len()];
+        for (i, out) in self.edges.iter().enumerate() {
+            for j in out {
+                undirected[i].push(*j);
+                undirected[*j].push(i);
+            }
+        }
+
+        let mut component_of = vec![None; self.len()];
+        let mut components: Vec<Vec<usize>> = vec![];
+        for start in 0..self.len() {
+            if component_of[start].is_some() {
+                continue;
+            }
+            let id = components.len();
+            component_of[start] = Some(id);
+            let mut members = vec![];
+            let mut stack = vec![start];
+            while let Some(i) = stack.pop() {
+                members.push(i);
+                for j in &undirected[i] {
+                    if component_of[*j].is_none() {
+                        component_of[*j] = Some(id);
+                        stack.push(*j);
+                    }
+                }
+            }
+            members.sort_unstable();
+            components.push(members);
+        }
+
+        components
+            .into_iter()
+            .map(|members| members.into_iter().map(|i| self.nodes[i].clone()).collect())
+            .collect()
+    }
+}
+
+struct Tarjan<'a> {
+    edges: &'a [Vec<usize>],
+    next_index: usize,
+    index: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, i: usize) {
+        self.index[i] = Some(self.next_index);
+        self.low_link[i] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(i);
+        self.on_stack[i] = true;
+
+        for &j in &self.edges[i] {
+            match self.index[j] {
+                None => {
+                    self.visit(j);
+                    self.low_link[i] = self.low_link[i].min(self.low_link[j]);
+                }
+                Some(index) if self.on_stack[j] => {
+                    self.low_link[i] = self.low_link[i].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_link[i]) == self.index[i] {
+            let mut component = vec![];
+            loop {
+                let j = self.stack.pop().unwrap();
+                self.on_stack[j] = false;
+                component.push(j);
+                if j == i {
+                    break;
+                }
+            }
+            component.reverse();
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024 day 5's page ordering rules.
+    fn rules() -> Graph<u16> {
+        [
+            (47, 53),
+            (97, 13),
+            (97, 61),
+            (97, 47),
+            (75, 29),
+            (61, 13),
+            (75, 53),
+            (29, 13),
+            (97, 29),
+            (53, 29),
+            (61, 53),
+            (97, 53),
+            (61, 29),
+            (47, 13),
+            (75, 47),
+            (97, 75),
+            (47, 61),
+            (75, 61),
+            (47, 29),
+            (75, 13),
+            (53, 13),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn sample() {
+        let rules = rules();
+        assert_eq!(7, rules.len());
+        assert!(rules.has_edge(&47, &53));
+        assert!(!rules.has_edge(&53, &47));
+        assert_eq!(21, rules.edges().count());
+
+        assert_eq!(
+            Ok(vec![97, 75, 47, 61, 53, 29, 13]),
+            rules.topological_sort()
+        );
+
+        // fixing 97,13,75,29,47
+        let update = rules.subgraph(&[97, 13, 75, 29, 47]);
+        assert_eq!(vec![97, 13, 75, 29, 47], update.nodes());
+        assert_eq!(Ok(vec![97, 75, 47, 29, 13]), update.topological_sort());
+
+        let with_stranger = rules.subgraph(&[13, 5, 97]);
+        assert_eq!(Ok(vec![5, 97, 13]), with_stranger.topological_sort());
+    }
+
+    #[test]
+    fn cycles() {
+        let mut graph: Graph<&str> = [("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("e", "f")]
+            .into_iter()
+            .collect();
+        graph.add_node("g");
+
+        let cycle = graph.topological_sort().unwrap_err();
+        assert_eq!(vec!["a", "b", "c", "a"], cycle.nodes);
+        assert_eq!("cycle: a -> b -> c -> a", cycle.to_string());
+
+        assert_eq!(
+            vec![
+                vec!["d"],
+                vec!["a", "b", "c"],
+                vec!["f"],
+                vec!["e"],
+                vec!["g"]
+            ],
+            graph.strongly_connected_components()
+        );
+        assert_eq!(
+            vec![vec!["a", "b", "c", "d"], vec!["e", "f"], vec!["g"]],
+            graph.connected_components()
+        );
+
+        let without_c = graph.subgraph(&["a", "b", "d"]);
+        assert_eq!(Ok(vec!["a", "b", "d"]), without_c.topological_sort());
+        assert_eq!(vec![&"b"], graph.neighbours(&"a").collect::<Vec<_>>());
+        assert_eq!(0, graph.neighbours(&"z").count());
+    }
+}
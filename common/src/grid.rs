@@ -0,0 +1,485 @@
+//! Grids of cells. [`Grid`] is the plain dense one; [`Torus`], [`Padded`] and [`Sparse`] change
+//! what happens past the edges, and [`Cells`] lets a solution work with any of them.
+
+use std::ops::{Index, IndexMut};
+
+use crate::hash::FastMap;
+
+/// A `(row, col)` that's allowed to wander off the grid.
+pub type Pos = (isize, isize);
+
+/// Up, right, down and left.
+pub const ORTHOGONAL: [Pos; 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// All eight cells around one, clockwise from the top left.
+pub const SURROUNDING: [Pos; 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+/// What every kind of grid can do, so a solution can swap storage without changing its logic.
+pub trait Cells {
+    type Cell: Copy;
+
+    /// Where `pos` really is on this grid, or `None` if it doesn't exist. A torus wraps it around,
+    /// a bounded grid drops it once it's past the edge.
+    fn locate(&self, pos: Pos) -> Option<Pos>;
+
+    fn cell(&self, pos: Pos) -> Option<Self::Cell>;
+
+    fn set_cell(&mut self, pos: Pos, value: Self::Cell);
+
+    /// The positions `offsets` away from `pos` that exist, already located.
+    fn neighbours<'a>(&'a self, pos: Pos, offsets: &'a [Pos]) -> impl Iterator<Item = Pos> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |(dr, dc)| self.locate((pos.0 + dr, pos.1 + dc)))
+    }
+}
+
+/// Turns a position into real indices, if it's inside `width` x `height`.
+fn bounded(pos: Pos, width: usize, height: usize) -> Option<(usize, usize)> {
+    let (row, col) = (usize::try_from(pos.0).ok()?, usize::try_from(pos.1).ok()?);
+    (row < height && col < width).then_some((row, col))
+}
+
+/// A dense rectangle of cells, stored row by row and indexed as `(row, col)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| f(row, col))
+            .collect();
+
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// One cell per byte of each line. Lines have to be the same length.
+    pub fn from_lines<'a, I>(lines: I, mut f: impl FnMut(u8) -> T) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut cells = vec![];
+        let mut width = 0;
+        let mut height = 0;
+        for line in lines {
+            width = line.len();
+            height += 1;
+            cells.extend(line.bytes().map(&mut f));
+        }
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "lines aren't all the same length"
+        );
+
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    /// Every cell with its `(row, col)`, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width.max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i / width, i % width), cell))
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(&mut f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(
+            col < self.width,
+            "column {col} is outside a width of {}",
+            self.width
+        );
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(
+            col < self.width,
+            "column {col} is outside a width of {}",
+            self.width
+        );
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+impl<T: Copy> Cells for Grid<T> {
+    type Cell = T;
+
+    fn locate(&self, pos: Pos) -> Option<Pos> {
+        bounded(pos, self.width, self.height).map(|_| pos)
+    }
+
+    fn cell(&self, pos: Pos) -> Option<T> {
+        let (row, col) = bounded(pos, self.width, self.height)?;
+        Some(self[(row, col)])
+    }
+
+    /// # Panics
+    ///
+    /// When the cell is outside the grid.
+    fn set_cell(&mut self, pos: Pos, value: T) {
+        let (row, col) = bounded(pos, self.width, self.height)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a {}x{} grid", self.width, self.height));
+        self[(row, col)] = value;
+    }
+}
+
+/// A grid whose edges join up: walking off the right comes back on the left, and off the bottom
+/// comes back at the top.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Torus<T> {
+    grid: Grid<T>,
+}
+
+impl<T> Torus<T> {
+    pub fn new(grid: Grid<T>) -> Self {
+        Self { grid }
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height
+    }
+
+    /// The real `(row, col)` any position lands on.
+    pub fn wrap(&self, (row, col): Pos) -> (usize, usize) {
+        (
+            row.rem_euclid(self.grid.height as isize) as usize,
+            col.rem_euclid(self.grid.width as isize) as usize,
+        )
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_inner(self) -> Grid<T> {
+        self.grid
+    }
+}
+
+impl<T> Index<Pos> for Torus<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        &self.grid[self.wrap(pos)]
+    }
+}
+
+impl<T> IndexMut<Pos> for Torus<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let at = self.wrap(pos);
+        &mut self.grid[at]
+    }
+}
+
+impl<T: Copy> Cells for Torus<T> {
+    type Cell = T;
+
+    fn locate(&self, pos: Pos) -> Option<Pos> {
+        let (row, col) = self.wrap(pos);
+        Some((row as isize, col as isize))
+    }
+
+    fn cell(&self, pos: Pos) -> Option<T> {
+        Some(self[pos])
+    }
+
+    fn set_cell(&mut self, pos: Pos, value: T) {
+        self[pos] = value;
+    }
+}
+
+/// A grid surrounded by an endless border of one value, so looking past the edge is never a
+/// special case. The border can be read but not written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Padded<T> {
+    grid: Grid<T>,
+    border: T,
+}
+
+impl<T> Padded<T> {
+    pub fn new(grid: Grid<T>, border: T) -> Self {
+        Self { grid, border }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_inner(self) -> Grid<T> {
+        self.grid
+    }
+}
+
+impl<T> Index<Pos> for Padded<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match bounded(pos, self.grid.width, self.grid.height) {
+            Some(at) => &self.grid[at],
+            None => &self.border,
+        }
+    }
+}
+
+impl<T: Copy> Cells for Padded<T> {
+    type Cell = T;
+
+    fn locate(&self, pos: Pos) -> Option<Pos> {
+        Some(pos)
+    }
+
+    fn cell(&self, pos: Pos) -> Option<T> {
+        Some(self[pos])
+    }
+
+    /// # Panics
+    ///
+    /// When the cell is part of the border.
+    fn set_cell(&mut self, pos: Pos, value: T) {
+        self.grid.set_cell(pos, value);
+    }
+}
+
+/// A grid with no edges that only stores the cells that have been set. Everything else reads as
+/// the default.
+#[derive(Debug, Clone)]
+pub struct Sparse<T> {
+    cells: FastMap<Pos, T>,
+    default: T,
+}
+
+impl<T> Sparse<T> {
+    pub fn new(default: T) -> Self {
+        Self {
+            cells: FastMap::default(),
+            default,
+        }
+    }
+
+    /// How many cells have been set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn remove(&mut self, pos: Pos) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    /// The cells that have been set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.cells.iter().map(|(pos, cell)| (*pos, cell))
+    }
+
+    /// The smallest `(top left, bottom right)` holding every cell that's been set.
+    pub fn bounds(&self) -> Option<(Pos, Pos)> {
+        self.cells.keys().fold(None, |bounds, &(row, col)| {
+            Some(match bounds {
+                None => ((row, col), (row, col)),
+                Some(((top, left), (bottom, right))) => (
+                    (top.min(row), left.min(col)),
+                    (bottom.max(row), right.max(col)),
+                ),
+            })
+        })
+    }
+}
+
+impl<T> Index<Pos> for Sparse<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.cells.get(&pos).unwrap_or(&self.default)
+    }
+}
+
+impl<T: Copy> Cells for Sparse<T> {
+    type Cell = T;
+
+    fn locate(&self, pos: Pos) -> Option<Pos> {
+        Some(pos)
+    }
+
+    fn cell(&self, pos: Pos) -> Option<T> {
+        Some(self[pos])
+    }
+
+    fn set_cell(&mut self, pos: Pos, value: T) {
+        self.cells.insert(pos, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        let mut grid = Grid::from_lines(["MMMS", "MSAM", "AMXS"], |b| b as char);
+        assert_eq!((4, 3), (grid.width(), grid.height()));
+        assert_eq!('X', grid[(2, 2)]);
+        assert_eq!(Some(&'S'), grid.get(0, 3));
+        assert_eq!(None, grid.get(0, 4));
+        assert_eq!(None, grid.get(3, 0));
+
+        grid[(0, 0)] = '.';
+        assert_eq!(&['.', 'M', 'M', 'S'], grid.row(0));
+
+        let xs: Vec<(usize, usize)> = grid
+            .iter()
+            .filter(|(_, c)| **c == 'X')
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(vec![(2, 2)], xs);
+
+        let counts = Grid::from_fn(2, 2, |row, col| row * 2 + col);
+        assert_eq!(Grid::new(2, 2, 1).map(|_| 0).width(), counts.width());
+        assert_eq!(
+            vec![&[0, 1][..], &[2, 3][..]],
+            counts.rows().collect::<Vec<_>>()
+        );
+    }
+
+    /// The same flood fill over each kind of grid, only the storage changes.
+    fn reachable<G: Cells<Cell = u8>>(grid: &G, start: Pos, limit: usize) -> usize {
+        let mut seen = Sparse::new(false);
+        seen.set_cell(start, true);
+        let mut queue = vec![start];
+        while let Some(pos) = queue.pop() {
+            for next in grid.neighbours(pos, &ORTHOGONAL) {
+                if grid.cell(next) == Some(b'.') && !seen[next] && seen.len() < limit {
+                    seen.set_cell(next, true);
+                    queue.push(next);
+                }
+            }
+        }
+        seen.len()
+    }
+
+    #[test]
+    fn variants() {
+        let lines = ["..#", "###", "..#"];
+        let grid = Grid::from_lines(lines, |b| b);
+        // bounded, the top left corner is walled in
+        assert_eq!(2, reachable(&grid, (0, 0), 100));
+        assert_eq!(None, grid.cell((-1, 0)));
+        assert_eq!(2, grid.neighbours((0, 0), &ORTHOGONAL).count());
+
+        // wrapped, it meets the bottom left corner
+        let torus = Torus::new(grid.clone());
+        assert_eq!(4, reachable(&torus, (0, 0), 100));
+        assert_eq!((2, 0), torus.wrap((-1, 3)));
+        assert_eq!(b'#', torus[(4, -1)]);
+        assert_eq!(8, torus.neighbours((0, 0), &SURROUNDING).count());
+
+        // padded with floor, it escapes around the outside forever
+        let padded = Padded::new(grid.clone(), b'.');
+        assert_eq!(50, reachable(&padded, (0, 0), 50));
+        assert_eq!(Some(b'.'), padded.cell((-7, 12)));
+        assert_eq!(Some(b'#'), padded.cell((1, 1)));
+
+        let mut sparse = Sparse::new(b'#');
+        for (pos, cell) in grid.iter() {
+            if *cell == b'.' {
+                sparse.set_cell((pos.0 as isize, pos.1 as isize), b'.');
+            }
+        }
+        assert_eq!(2, reachable(&sparse, (0, 0), 100));
+        assert_eq!(Some(((0, 0), (2, 1))), sparse.bounds());
+        assert_eq!(Some(b'.'), sparse.remove((2, 1)));
+        assert_eq!(b'#', sparse[(2, 1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "(-1, 0) is outside a 3x2 grid")]
+    fn padded_border() {
+        let mut padded = Padded::new(Grid::new(3, 2, 0), 9);
+        padded.set_cell((-1, 0), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "column 4")]
+    fn wide_index() {
+        let grid = Grid::new(4, 2, 0);
+        let _ = grid[(0, 4)];
+    }
+}
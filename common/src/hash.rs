@@ -0,0 +1,108 @@
+//! A fast, non-cryptographic hasher for the maps and sets in hot loops.
+//!
+//! The default `SipHash` is built to resist hash flooding, which puzzle input never attempts.
+//! [`FxHasher`] is the multiply-and-rotate hash rustc uses internally: a couple of instructions
+//! per word, and good enough spread for integer and small tuple keys.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hasher},
+};
+
+const SEED: u64 = 0xf1_35_7a_ea_2e_62_a9_c5;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in chunks.by_ref() {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.add(i as u64);
+        self.add((i >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        // the multiply leaves the best bits at the top, and hashbrown picks buckets from the bottom
+        self.hash.rotate_left(26)
+    }
+}
+
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+pub type FastMap<K, V> = HashMap<K, V, FxBuildHasher>;
+pub type FastSet<T> = HashSet<T, FxBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasher, Hash};
+
+    use super::*;
+
+    fn hash<T: Hash>(value: T) -> u64 {
+        FxBuildHasher::default().hash_one(value)
+    }
+
+    #[test]
+    fn sample() {
+        let mut counts: FastMap<(usize, usize), usize> = FastMap::default();
+        for position in [(3, 5), (5, 4), (3, 5), (9, 6), (3, 5)] {
+            *counts.entry(position).or_default() += 1;
+        }
+        assert_eq!(Some(&3), counts.get(&(3, 5)));
+        assert_eq!(3, counts.len());
+
+        let set: FastSet<&str> = ["ab", "cd", "ab"].into_iter().collect();
+        assert_eq!(2, set.len());
+
+        assert_eq!(hash((1_u64, 2_u64)), hash((1_u64, 2_u64)));
+        assert_ne!(hash((1_u64, 2_u64)), hash((2_u64, 1_u64)));
+        assert_ne!(hash("abcdefgh1"), hash("abcdefgh2"));
+    }
+
+    #[test]
+    fn spread() {
+        // neighbouring keys should land all over the place, not in neighbouring buckets
+        let buckets: FastSet<u64> = (0..1024_u64).map(|n| hash(n) & 1023).collect();
+        assert!(buckets.len() > 600, "only {} buckets used", buckets.len());
+    }
+}
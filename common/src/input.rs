@@ -0,0 +1,188 @@
+//! Reading puzzle input: the whole file in one go, then borrowed views of it.
+
+use std::{fs, io, path::Path, str::FromStr};
+
+use crate::{
+    grid::Grid,
+    ints::{Ints, ParseInt, ints},
+};
+
+/// A whole input file, read into one buffer. Lines, sections, grids and numbers all borrow from
+/// it, so nothing gets allocated per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    text: String,
+}
+
+impl From<String> for Input {
+    fn from(text: String) -> Self {
+        Self { text }
+    }
+}
+
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        Self::from(text.to_string())
+    }
+}
+
+impl Input {
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(path).map(Self::from)
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        self.text.as_bytes()
+    }
+
+    /// Every line, without its `\n` or `\r\n`.
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.text.lines()
+    }
+
+    /// [`Input::lines`] as bytes, for parsers that don't care about UTF-8.
+    pub fn byte_lines(&self) -> impl Iterator<Item = &[u8]> {
+        self.lines().map(str::as_bytes)
+    }
+
+    /// Blocks of lines split on blank ones, like [`sections`].
+    pub fn sections(&self) -> Sections<std::str::Lines<'_>> {
+        sections(self.lines())
+    }
+
+    /// One cell per byte of each line.
+    pub fn grid<T>(&self, f: impl FnMut(u8) -> T) -> Grid<T> {
+        Grid::from_lines(self.lines(), f)
+    }
+
+    /// Every integer anywhere in the file, in order.
+    pub fn ints<T: ParseInt>(&self) -> Ints<'_, T> {
+        ints(&self.text)
+    }
+}
+
+/// A block of consecutive non-blank lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    pub lines: Vec<&'a str>,
+}
+
+impl Section<'_> {
+    /// The lines joined back together with `\n`.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Parses the whole block as one value, e.g. a struct whose pattern spans several lines.
+    pub fn parse<T: FromStr>(&self) -> Result<T, T::Err> {
+        self.text().parse()
+    }
+
+    /// Parses every line of the block on its own.
+    pub fn parse_lines<T: FromStr>(&self) -> Result<Vec<T>, T::Err> {
+        self.lines.iter().map(|line| line.parse()).collect()
+    }
+}
+
+/// Iterator returned by [`sections`].
+pub struct Sections<I> {
+    lines: I,
+}
+
+impl<'a, I: Iterator<Item = &'a str>> Iterator for Sections<I> {
+    type Item = Section<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let lines: Vec<&str> = self
+            .lines
+            .by_ref()
+            .map(|line| line.trim_end_matches('\r'))
+            .skip_while(|line| line.trim().is_empty())
+            .take_while(|line| !line.trim().is_empty())
+            .collect();
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(Section { lines })
+        }
+    }
+}
+
+/// Splits lines into blocks separated by blank lines. Leading, trailing and repeated blank lines
+/// never produce empty sections, and a `\r` left over from CRLF line endings is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_common::input::sections;
+///
+/// let input = "47|53\n97|13\n\n75,47,61\n";
+/// let blocks: Vec<_> = sections(input.lines()).map(|s| s.lines).collect();
+/// assert_eq!(vec![vec!["47|53", "97|13"], vec!["75,47,61"]], blocks);
+/// ```
+pub fn sections<'a, I>(lines: I) -> Sections<I::IntoIter>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    Sections {
+        lines: lines.into_iter(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        let input = "\r\n3-5\r\n10-14\r\n\r\n\r\n\r\n1\r\n5\r\n   \r\n8\r\n\r\n";
+        let blocks: Vec<Section> = sections(input.split('\n')).collect();
+
+        assert_eq!(3, blocks.len());
+        assert_eq!(vec!["3-5", "10-14"], blocks[0].lines);
+        assert_eq!("1\n5", blocks[1].text());
+        assert_eq!(Ok(vec![1, 5]), blocks[1].parse_lines::<u32>());
+        assert_eq!(Ok(8), blocks[2].parse::<u8>());
+    }
+
+    #[test]
+    fn owned_lines() {
+        let lines: Vec<String> = vec!["a".into(), "".into(), "b".into(), "c".into()];
+        let mut blocks = sections(lines.iter().map(String::as_str));
+
+        assert_eq!(Some(vec!["a"]), blocks.next().map(|s| s.lines));
+        assert_eq!(Some(vec!["b", "c"]), blocks.next().map(|s| s.lines));
+        assert_eq!(None, blocks.next());
+        assert_eq!(0, sections("\n\n\n".lines()).count());
+    }
+
+    #[test]
+    fn input() {
+        let input = Input::from("3-5\r\n10-14\r\n\r\n1\r\n17\r\n");
+        assert_eq!(
+            vec!["3-5", "10-14", "", "1", "17"],
+            input.lines().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&b"10-14"[..]), input.byte_lines().nth(1));
+        assert_eq!(
+            vec![vec!["3-5", "10-14"], vec!["1", "17"]],
+            input.sections().map(|s| s.lines).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![3, 5, 10, 14, 1, 17],
+            input.ints::<u64>().collect::<Vec<_>>()
+        );
+        assert_eq!(21, input.bytes().len());
+
+        let grid = Input::from("#.\n.#\n").grid(|b| b == b'#');
+        assert_eq!((2, 2), (grid.width(), grid.height()));
+        assert!(grid[(1, 1)] && !grid[(1, 0)]);
+
+        assert!(Input::read("does/not/exist.txt").is_err());
+    }
+}
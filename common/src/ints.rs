@@ -0,0 +1,241 @@
+//! Pulls every integer out of a line in one pass over its bytes, whatever is between them.
+//!
+//! ```
+//! use aoc_common::ints::{ints, ints_n};
+//!
+//! let robot: Vec<i32> = ints("p=0,4 v=3,-3").collect();
+//! assert_eq!(vec![0, 4, 3, -3], robot);
+//! let [x, y, z] = ints_n::<3, u64>("162,817,812");
+//! assert_eq!((162, 817, 812), (x, y, z));
+//! ```
+//!
+//! A `-` counts as a sign when it isn't straight after a digit, so `1-3` is a range, not `1` and
+//! `-3`. That goes for unsigned types too: `-3` read as a `u8` doesn't fit, the same as `300`
+//! doesn't, rather than quietly coming out as `3`.
+
+use std::fmt::Display;
+
+use crate::checked::Int;
+
+/// The integers [`ints`] knows how to read.
+pub trait ParseInt: Int {
+    /// `n` as this type. Only ever called with a digit or 10, which fit in everything.
+    fn small(n: u8) -> Self;
+}
+
+macro_rules! parse_int {
+    ($($t:ty),*) => {$(
+        impl ParseInt for $t {
+            fn small(n: u8) -> Self {
+                n as $t
+            }
+        }
+    )*};
+}
+
+parse_int!(u8, u16, u32, u64, u128, usize);
+parse_int!(i8, i16, i32, i64, i128, isize);
+
+/// A number in the input that's too big for the type it was read as, or negative when the type
+/// can't be.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TooBig {
+    pub digits: String,
+    pub type_name: &'static str,
+}
+
+impl Display for TooBig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} doesn't fit in {}", self.digits, self.type_name)
+    }
+}
+
+impl std::error::Error for TooBig {}
+
+/// The integers in a line, in order. See [`ints`].
+#[derive(Debug, Clone)]
+pub struct Ints<'a, T> {
+    bytes: &'a [u8],
+    at: usize,
+    marker: std::marker::PhantomData<T>,
+}
+
+impl<T: ParseInt> Ints<'_, T> {
+    /// The next integer, or what went wrong reading it. Either way the whole number is used up.
+    pub fn try_next(&mut self) -> Option<Result<T, TooBig>> {
+        let bytes = self.bytes;
+        let digit = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_digit);
+
+        let start = (self.at..bytes.len()).find(|&i| {
+            digit(i) || (bytes[i] == b'-' && digit(i + 1) && !(i > 0 && digit(i - 1)))
+        })?;
+        let negative = bytes[start] == b'-';
+        let first = if negative { start + 1 } else { start };
+        let end = (first..bytes.len())
+            .find(|&i| !digit(i))
+            .unwrap_or(bytes.len());
+        self.at = end;
+
+        // negative numbers are built downwards, so the most negative value still fits
+        let value = bytes[first..end].iter().try_fold(T::ZERO, |value, b| {
+            let value = value.try_mul(T::small(10))?;
+            let digit = T::small(b - b'0');
+            if negative {
+                value.try_sub(digit)
+            } else {
+                value.try_add(digit)
+            }
+        });
+
+        Some(value.ok_or_else(|| TooBig {
+            digits: String::from_utf8_lossy(&bytes[start..end]).into_owned(),
+            type_name: std::any::type_name::<T>(),
+        }))
+    }
+}
+
+impl<T: ParseInt> Iterator for Ints<'_, T> {
+    type Item = T;
+
+    /// # Panics
+    ///
+    /// When a number doesn't fit in `T`.
+    fn next(&mut self) -> Option<T> {
+        self.try_next()
+            .map(|value| value.unwrap_or_else(|e| panic!("{e}")))
+    }
+}
+
+/// Every integer in `line`, as `T`. Panics on one that doesn't fit; use [`try_ints`] to get an
+/// error instead.
+pub fn ints<T: ParseInt>(line: &(impl AsRef<[u8]> + ?Sized)) -> Ints<'_, T> {
+    Ints {
+        bytes: line.as_ref(),
+        at: 0,
+        marker: std::marker::PhantomData,
+    }
+}
+
+/// Every integer in `line`, collected into a `Vec`. Stops at the first one that doesn't fit in
+/// `T` and returns its [`TooBig`] instead.
+pub fn try_ints<T: ParseInt>(line: &(impl AsRef<[u8]> + ?Sized)) -> Result<Vec<T>, TooBig> {
+    let mut found = ints(line);
+    std::iter::from_fn(|| found.try_next()).collect()
+}
+
+/// Exactly `N` integers from `line`.
+///
+/// # Panics
+///
+/// When there aren't exactly `N` of them, or one doesn't fit in `T`.
+pub fn ints_n<const N: usize, T: ParseInt>(line: &(impl AsRef<[u8]> + ?Sized)) -> [T; N] {
+    let mut found = ints(line);
+    let values = std::array::from_fn(|i| {
+        found.next().unwrap_or_else(|| {
+            panic!(
+                "expected {N} integers in {:?} but there were only {i}",
+                String::from_utf8_lossy(line.as_ref())
+            )
+        })
+    });
+    assert!(
+        found.next().is_none(),
+        "expected {N} integers in {:?} but there were more",
+        String::from_utf8_lossy(line.as_ref())
+    );
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        assert_eq!(vec![3, 4], ints::<u32>("3   4").collect::<Vec<_>>());
+        assert_eq!(
+            vec![7, 6, 4, 2, 1],
+            ints::<u16>("7 6 4 2 1").collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![94, 34, 8400, 5400],
+            ints::<u64>("Button A: X+94, Y+34 ... Prize: X=8400, Y=5400").collect::<Vec<_>>()
+        );
+        assert_eq!(0, ints::<i64>("no numbers - at all").count());
+        assert_eq!(0, ints::<i64>("").count());
+
+        // signs, but not straight after a digit
+        assert_eq!(vec![-3, 2], ints::<i8>("-3,2").collect::<Vec<_>>());
+        assert_eq!(vec![1, 3], ints::<u8>("1-3").collect::<Vec<_>>());
+        assert_eq!(vec![11, 22], ints::<i64>("11-22").collect::<Vec<_>>());
+        assert_eq!(vec![-5], ints::<i64>("--5").collect::<Vec<_>>());
+        assert_eq!(vec![5], ints::<i64>("- 5").collect::<Vec<_>>());
+
+        assert_eq!([7, 1], ints_n::<2, u64>(b"7,1"));
+        assert_eq!(
+            [162, 817, 812],
+            ints_n::<3, i64>(&String::from("162,817,812"))
+        );
+    }
+
+    #[test]
+    fn edges() {
+        assert_eq!(vec![i8::MIN, i8::MAX], try_ints::<i8>("-128 127").unwrap());
+        assert_eq!(
+            vec![u64::MAX],
+            try_ints::<u64>("18446744073709551615").unwrap()
+        );
+        assert_eq!(vec![0, 7], try_ints::<u8>("000 007").unwrap());
+
+        let too_big = try_ints::<i8>("1 -129 2").unwrap_err();
+        assert_eq!("-129 doesn't fit in i8", too_big.to_string());
+        assert_eq!(
+            Err(TooBig {
+                digits: "18446744073709551616".to_string(),
+                type_name: "u64"
+            }),
+            try_ints::<u64>("18446744073709551616")
+        );
+
+        // an overflow only spoils the number it happened in
+        let mut found = ints::<u8>("256,1");
+        assert!(found.try_next().unwrap().is_err());
+        assert_eq!(Some(1), found.next());
+    }
+
+    #[test]
+    #[should_panic(expected = "300 doesn't fit in u8")]
+    fn overflow() {
+        let _: Vec<u8> = ints("1 300").collect();
+    }
+
+    #[test]
+    fn negative_unsigned() {
+        assert_eq!(
+            "-3 doesn't fit in u8",
+            try_ints::<u8>("1,-3,2").unwrap_err().to_string()
+        );
+        assert_eq!(vec![0], try_ints::<u32>("-0").unwrap());
+        let mut found = ints::<u64>("-3,2");
+        assert!(found.try_next().unwrap().is_err());
+        assert_eq!(Some(2), found.next());
+    }
+
+    #[test]
+    #[should_panic(expected = "-3 doesn't fit in usize")]
+    fn negative_unsigned_panics() {
+        ints_n::<2, usize>("-3,2");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 3 integers in \"1,2\" but there were only 2")]
+    fn too_few() {
+        ints_n::<3, u8>("1,2");
+    }
+
+    #[test]
+    #[should_panic(expected = "but there were more")]
+    fn too_many() {
+        ints_n::<1, u8>("1,2");
+    }
+}
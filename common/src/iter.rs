@@ -0,0 +1,437 @@
+//! Extra iterator adapters the standard library doesn't have.
+
+use std::hash::Hash;
+
+use crate::hash::FastMap;
+
+/// What [`IterExt::fold_while`] closures return: keep going, or stop with this value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldWhile<T> {
+    Continue(T),
+    Done(T),
+}
+
+impl<T> FoldWhile<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            FoldWhile::Continue(value) | FoldWhile::Done(value) => value,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        matches!(self, FoldWhile::Done(_))
+    }
+}
+
+/// A tuple of identical items that [`IterExt::tuple_windows`] can slide along.
+pub trait TupleWindow: Clone {
+    type Item;
+
+    fn start(iter: &mut impl Iterator<Item = Self::Item>) -> Option<Self>;
+    fn shift(self, next: Self::Item) -> Self;
+}
+
+impl<T: Clone> TupleWindow for (T, T) {
+    type Item = T;
+
+    fn start(iter: &mut impl Iterator<Item = T>) -> Option<Self> {
+        Some((iter.next()?, iter.next()?))
+    }
+
+    fn shift(self, next: T) -> Self {
+        (self.1, next)
+    }
+}
+
+impl<T: Clone> TupleWindow for (T, T, T) {
+    type Item = T;
+
+    fn start(iter: &mut impl Iterator<Item = T>) -> Option<Self> {
+        Some((iter.next()?, iter.next()?, iter.next()?))
+    }
+
+    fn shift(self, next: T) -> Self {
+        (self.1, self.2, next)
+    }
+}
+
+impl<T: Clone> TupleWindow for (T, T, T, T) {
+    type Item = T;
+
+    fn start(iter: &mut impl Iterator<Item = T>) -> Option<Self> {
+        Some((iter.next()?, iter.next()?, iter.next()?, iter.next()?))
+    }
+
+    fn shift(self, next: T) -> Self {
+        (self.1, self.2, self.3, next)
+    }
+}
+
+pub struct TupleWindows<I, W> {
+    iter: I,
+    last: Option<W>,
+}
+
+impl<I, W> Iterator for TupleWindows<I, W>
+where
+    I: Iterator,
+    W: TupleWindow<Item = I::Item>,
+{
+    type Item = W;
+
+    fn next(&mut self) -> Option<W> {
+        let window = match self.last.take() {
+            None => W::start(&mut self.iter)?,
+            Some(last) => last.shift(self.iter.next()?),
+        };
+        self.last = Some(window.clone());
+        Some(window)
+    }
+}
+
+/// Every length-`n` sequence drawn from the pool, with repeats, like an odometer: the last
+/// position turns fastest.
+pub struct CartesianPower<T> {
+    pool: Vec<T>,
+    indices: Option<Vec<usize>>,
+    current: Vec<usize>,
+}
+
+impl<T> CartesianPower<T> {
+    /// The next sequence as positions in the pool, without allocating. The slice is reused, so it
+    /// only lasts until the next call.
+    pub fn next_indices(&mut self) -> Option<&[usize]> {
+        self.advance().then_some(&self.current)
+    }
+
+    fn advance(&mut self) -> bool {
+        let Some(indices) = self.indices.as_mut() else {
+            return false;
+        };
+        self.current.clone_from(indices);
+
+        // tick the odometer, and stop once it rolls all the way over
+        let rolled = indices.iter_mut().rev().all(|i| {
+            *i += 1;
+            if *i == self.pool.len() {
+                *i = 0;
+                true
+            } else {
+                false
+            }
+        });
+        if rolled {
+            self.indices = None;
+        }
+        true
+    }
+}
+
+impl<T: Clone> Iterator for CartesianPower<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        self.advance().then(|| picked(&self.pool, &self.current))
+    }
+}
+
+/// Every `k` items from the pool in their original order, without repeats.
+pub struct Combinations<T> {
+    pool: Vec<T>,
+    indices: Option<Vec<usize>>,
+    current: Vec<usize>,
+}
+
+impl<T> Combinations<T> {
+    /// The next combination as positions in the pool, without allocating. The slice is reused, so
+    /// it only lasts until the next call.
+    pub fn next_indices(&mut self) -> Option<&[usize]> {
+        self.advance().then_some(&self.current)
+    }
+
+    fn advance(&mut self) -> bool {
+        let Some(indices) = self.indices.as_mut() else {
+            return false;
+        };
+        self.current.clone_from(indices);
+
+        // find the rightmost index that can still move right, move it, and pack the rest behind it
+        let (n, k) = (self.pool.len(), indices.len());
+        match (0..k).rev().find(|&i| indices[i] < n - k + i) {
+            Some(i) => {
+                indices[i] += 1;
+                for j in i + 1..k {
+                    indices[j] = indices[j - 1] + 1;
+                }
+            }
+            None => self.indices = None,
+        }
+        true
+    }
+}
+
+impl<T: Clone> Iterator for Combinations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        self.advance().then(|| picked(&self.pool, &self.current))
+    }
+}
+
+/// Every ordering of `k` distinct items from the pool, in lexicographic order of position.
+pub struct Permutations<T> {
+    pool: Vec<T>,
+    k: usize,
+    indices: Vec<usize>,
+    cycles: Vec<usize>,
+    first: bool,
+    done: bool,
+}
+
+impl<T> Permutations<T> {
+    /// The next ordering as positions in the pool, without allocating. The slice is reused, so it
+    /// only lasts until the next call.
+    pub fn next_indices(&mut self) -> Option<&[usize]> {
+        self.advance().then_some(&self.indices[..self.k])
+    }
+
+    fn advance(&mut self) -> bool {
+        if self.done {
+            return false;
+        }
+        if !self.first {
+            // same stepping as Python's itertools.permutations
+            let n = self.indices.len();
+            let advanced = (0..self.k).rev().any(|i| {
+                self.cycles[i] -= 1;
+                if self.cycles[i] == 0 {
+                    self.indices[i..].rotate_left(1);
+                    self.cycles[i] = n - i;
+                    false
+                } else {
+                    self.indices.swap(i, n - self.cycles[i]);
+                    true
+                }
+            });
+            if !advanced {
+                self.done = true;
+                return false;
+            }
+        }
+        self.first = false;
+        true
+    }
+}
+
+impl<T: Clone> Iterator for Permutations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        self.advance()
+            .then(|| picked(&self.pool, &self.indices[..self.k]))
+    }
+}
+
+fn picked<T: Clone>(pool: &[T], indices: &[usize]) -> Vec<T> {
+    indices.iter().map(|i| pool[*i].clone()).collect()
+}
+
+pub trait IterExt: Iterator + Sized {
+    /// Like `fold`, but the closure can stop early by returning [`FoldWhile::Done`]. Whatever's
+    /// left of the iterator stays unconsumed.
+    fn fold_while<B, F>(&mut self, init: B, mut f: F) -> FoldWhile<B>
+    where
+        F: FnMut(B, Self::Item) -> FoldWhile<B>,
+    {
+        let mut acc = init;
+        for item in self {
+            match f(acc, item) {
+                FoldWhile::Continue(next) => acc = next,
+                done => return done,
+            }
+        }
+        FoldWhile::Continue(acc)
+    }
+
+    /// How many times each item shows up.
+    fn counts(self) -> FastMap<Self::Item, usize>
+    where
+        Self::Item: Hash + Eq,
+    {
+        let mut counts = FastMap::default();
+        for item in self {
+            *counts.entry(item).or_default() += 1;
+        }
+        counts
+    }
+
+    /// The smallest and largest item in one pass. Ties go to the first minimum and last maximum,
+    /// the same as `min` and `max`.
+    fn min_max(mut self) -> Option<(Self::Item, Self::Item)>
+    where
+        Self::Item: Ord + Clone,
+    {
+        let first = self.next()?;
+        Some(self.fold((first.clone(), first), |(min, max), item| {
+            if item < min {
+                (item, max)
+            } else if item >= max {
+                (min, item)
+            } else {
+                (min, max)
+            }
+        }))
+    }
+
+    /// Overlapping windows as tuples of 2, 3 or 4: `[1, 2, 3]` gives `(1, 2)` then `(2, 3)`.
+    fn tuple_windows<W>(self) -> TupleWindows<Self, W>
+    where
+        W: TupleWindow<Item = Self::Item>,
+    {
+        TupleWindows {
+            iter: self,
+            last: None,
+        }
+    }
+
+    /// Every way of picking `n` items in a row, repeats allowed. `n == 0` gives one empty pick.
+    fn cartesian_power(self, n: usize) -> CartesianPower<Self::Item> {
+        let pool: Vec<Self::Item> = self.collect();
+        let indices = (n == 0 || !pool.is_empty()).then(|| vec![0; n]);
+        CartesianPower {
+            pool,
+            indices,
+            current: vec![],
+        }
+    }
+
+    fn combinations(self, k: usize) -> Combinations<Self::Item> {
+        let pool: Vec<Self::Item> = self.collect();
+        let indices = (k <= pool.len()).then(|| (0..k).collect());
+        Combinations {
+            pool,
+            indices,
+            current: vec![],
+        }
+    }
+
+    fn permutations(self, k: usize) -> Permutations<Self::Item> {
+        let pool: Vec<Self::Item> = self.collect();
+        let n = pool.len();
+        Permutations {
+            k,
+            indices: (0..n).collect(),
+            cycles: (0..k.min(n)).map(|i| n - i).collect(),
+            first: true,
+            done: k > n,
+            pool,
+        }
+    }
+}
+
+impl<I: Iterator> IterExt for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use FoldWhile::{Continue, Done};
+
+    #[test]
+    fn sample() {
+        // 2024 day 7: 3267: 81 40 27 with the first operator combo that gets too big
+        let mut values = [81, 40, 27].into_iter();
+        let result = values.fold_while(0, |acc, x| {
+            let next = acc + x * 100;
+            if next > 3267 {
+                Done(next)
+            } else {
+                Continue(next)
+            }
+        });
+        assert_eq!(Done(8100), result);
+        assert_eq!(Some(40), values.next());
+        assert_eq!(
+            Continue(148),
+            [81, 40, 27].iter().fold_while(0, |a, x| Continue(a + x))
+        );
+
+        let counts = "abracadabra".chars().counts();
+        assert_eq!(5, counts[&'a']);
+        assert_eq!(1, counts[&'d']);
+
+        assert_eq!(Some((1, 9)), [3, 1, 4, 1, 5, 9, 2, 6].into_iter().min_max());
+        assert_eq!(None, std::iter::empty::<u8>().min_max());
+
+        let pairs: Vec<(u8, u8)> = [1, 2, 3, 4].into_iter().tuple_windows().collect();
+        assert_eq!(vec![(1, 2), (2, 3), (3, 4)], pairs);
+        let triples: Vec<(u8, u8, u8)> = [1, 2, 3, 4].into_iter().tuple_windows().collect();
+        assert_eq!(vec![(1, 2, 3), (2, 3, 4)], triples);
+        assert_eq!(0, [1].into_iter().tuple_windows::<(u8, u8)>().count());
+    }
+
+    #[test]
+    fn combinatorics() {
+        let ops: Vec<String> = ['+', '*', '|']
+            .into_iter()
+            .cartesian_power(2)
+            .map(String::from_iter)
+            .collect();
+        assert_eq!(
+            vec!["++", "+*", "+|", "*+", "**", "*|", "|+", "|*", "||"],
+            ops
+        );
+        assert_eq!(3_usize.pow(11), (0..3).cartesian_power(11).count());
+        assert_eq!(1, (0..3).cartesian_power(0).count());
+        assert_eq!(0, std::iter::empty::<u8>().cartesian_power(2).count());
+
+        let combos: Vec<Vec<u8>> = (1..=4).combinations(2).collect();
+        assert_eq!(
+            vec![
+                vec![1, 2],
+                vec![1, 3],
+                vec![1, 4],
+                vec![2, 3],
+                vec![2, 4],
+                vec![3, 4]
+            ],
+            combos
+        );
+        assert_eq!(1, (1..=4).combinations(0).count());
+        assert_eq!(0, (1..=4).combinations(5).count());
+
+        let perms: Vec<Vec<u8>> = (1..=3).permutations(3).collect();
+        assert_eq!(
+            vec![
+                vec![1, 2, 3],
+                vec![1, 3, 2],
+                vec![2, 1, 3],
+                vec![2, 3, 1],
+                vec![3, 1, 2],
+                vec![3, 2, 1]
+            ],
+            perms
+        );
+        assert_eq!(12, (1..=4).permutations(2).count());
+        assert_eq!(0, (1..=2).permutations(3).count());
+        assert_eq!(1, (1..=2).permutations(0).count());
+
+        // the same again, one reused slice of positions at a time
+        let mut ops = ['+', '*', '|'].into_iter().cartesian_power(2);
+        let mut picks = vec![];
+        while let Some(indices) = ops.next_indices() {
+            picks.push(indices.to_vec());
+        }
+        assert_eq!(9, picks.len());
+        assert_eq!(vec![1, 2], picks[5]);
+        let mut combos = (1..=4).combinations(2);
+        assert_eq!(Some(&[0, 1][..]), combos.next_indices());
+        assert_eq!(
+            5,
+            std::iter::from_fn(|| combos.next_indices().map(<[usize]>::len)).count()
+        );
+        let mut perms = (1..=3).permutations(3);
+        assert_eq!(Some(&[0, 1, 2][..]), perms.next_indices());
+        assert_eq!(Some(&[0, 2, 1][..]), perms.next_indices());
+        assert_eq!(vec![vec![2, 1, 3]], perms.take(1).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,370 @@
+//! A k-d tree for nearest-neighbour questions about a fixed set of points.
+//!
+//! Distances are exact squared euclidean distances in `i64`. Ties are broken by the point's index
+//! in the original input, so every query has one right answer.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::point::{Point2, Point3};
+
+/// Something with integer coordinates along a fixed number of axes.
+pub trait KdPoint: Copy {
+    const DIMS: usize;
+
+    fn coord(&self, axis: usize) -> i64;
+
+    fn distance_squared(&self, other: &Self) -> i64 {
+        (0..Self::DIMS)
+            .map(|axis| (self.coord(axis) - other.coord(axis)).pow(2))
+            .sum()
+    }
+}
+
+impl KdPoint for Point2<i64> {
+    const DIMS: usize = 2;
+
+    fn coord(&self, axis: usize) -> i64 {
+        match axis {
+            0 => self.x,
+            _ => self.y,
+        }
+    }
+}
+
+impl KdPoint for Point3<i64> {
+    const DIMS: usize = 3;
+
+    fn coord(&self, axis: usize) -> i64 {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => self.z,
+        }
+    }
+}
+
+/// A query result: the squared distance, then the index of the point in the original input.
+pub type Neighbour = (i64, usize);
+
+/// A balanced k-d tree kept implicitly in one array: the median of every range is its node, the
+/// halves either side are its subtrees, and the split axis cycles with depth.
+#[derive(Debug, Clone)]
+pub struct KdTree<P> {
+    nodes: Vec<(P, usize)>,
+    points: Vec<P>,
+}
+
+impl<P: KdPoint> KdTree<P> {
+    pub fn new(points: Vec<P>) -> Self {
+        let mut nodes: Vec<(P, usize)> = points.iter().copied().zip(0..).collect();
+        Self::build(&mut nodes, 0);
+        Self { nodes, points }
+    }
+
+    fn build(nodes: &mut [(P, usize)], depth: usize) {
+        if nodes.len() <= 1 {
+            return;
+        }
+        let axis = depth % P::DIMS;
+        let mid = nodes.len() / 2;
+        nodes.select_nth_unstable_by_key(mid, |(p, i)| (p.coord(axis), *i));
+        let (left, right) = nodes.split_at_mut(mid);
+        Self::build(left, depth + 1);
+        Self::build(&mut right[1..], depth + 1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The point that was at `index` in the input.
+    pub fn point(&self, index: usize) -> &P {
+        &self.points[index]
+    }
+
+    pub fn nearest(&self, target: &P) -> Option<Neighbour> {
+        self.k_nearest(target, 1).pop()
+    }
+
+    /// The `k` closest points, closest first.
+    pub fn k_nearest(&self, target: &P, k: usize) -> Vec<Neighbour> {
+        let mut best: BinaryHeap<Neighbour> = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search_k(&self.nodes, 0, target, k, &mut best);
+        }
+        best.into_sorted_vec()
+    }
+
+    fn search_k(
+        &self,
+        nodes: &[(P, usize)],
+        depth: usize,
+        target: &P,
+        k: usize,
+        best: &mut BinaryHeap<Neighbour>,
+    ) {
+        if nodes.is_empty() {
+            return;
+        }
+        let mid = nodes.len() / 2;
+        let (point, index) = nodes[mid];
+
+        best.push((point.distance_squared(target), index));
+        if best.len() > k {
+            best.pop();
+        }
+
+        let axis = depth % P::DIMS;
+        let offset = target.coord(axis) - point.coord(axis);
+        let (near, far) = if offset < 0 {
+            (&nodes[..mid], &nodes[mid + 1..])
+        } else {
+            (&nodes[mid + 1..], &nodes[..mid])
+        };
+
+        self.search_k(near, depth + 1, target, k, best);
+        // equal distances still need a look, since the index might win the tie
+        let worst = best.peek().map_or(i64::MAX, |(d, _)| *d);
+        if best.len() < k || offset.pow(2) <= worst {
+            self.search_k(far, depth + 1, target, k, best);
+        }
+    }
+
+    /// Every point within `radius_squared` of the target, closest first.
+    pub fn within(&self, target: &P, radius_squared: i64) -> Vec<Neighbour> {
+        let mut found = vec![];
+        self.search_radius(&self.nodes, 0, target, radius_squared, &mut found);
+        found.sort_unstable();
+        found
+    }
+
+    fn search_radius(
+        &self,
+        nodes: &[(P, usize)],
+        depth: usize,
+        target: &P,
+        radius_squared: i64,
+        found: &mut Vec<Neighbour>,
+    ) {
+        if nodes.is_empty() {
+            return;
+        }
+        let mid = nodes.len() / 2;
+        let (point, index) = nodes[mid];
+
+        let distance = point.distance_squared(target);
+        if distance <= radius_squared {
+            found.push((distance, index));
+        }
+
+        let axis = depth % P::DIMS;
+        let offset = target.coord(axis) - point.coord(axis);
+        let (near, far) = if offset < 0 {
+            (&nodes[..mid], &nodes[mid + 1..])
+        } else {
+            (&nodes[mid + 1..], &nodes[..mid])
+        };
+
+        self.search_radius(near, depth + 1, target, radius_squared, found);
+        if offset.pow(2) <= radius_squared {
+            self.search_radius(far, depth + 1, target, radius_squared, found);
+        }
+    }
+
+    /// Every pair of points, closest pair first, as `(distance, i, j)` with `i < j`.
+    ///
+    /// Each point keeps a cursor into its own neighbour list and a heap picks the smallest next
+    /// step, so taking the first `n` pairs only looks at a few neighbours per point instead of
+    /// building all n² pairs.
+    pub fn closest_pairs(&self) -> ClosestPairs<'_, P> {
+        let mut pairs = ClosestPairs {
+            tree: self,
+            neighbours: vec![vec![]; self.len()],
+            fetched: vec![0; self.len()],
+            cursors: vec![0; self.len()],
+            heap: BinaryHeap::new(),
+        };
+        for i in 0..self.len() {
+            pairs.advance(i);
+        }
+        pairs
+    }
+}
+
+/// Iterator returned by [`KdTree::closest_pairs`].
+pub struct ClosestPairs<'a, P> {
+    tree: &'a KdTree<P>,
+    neighbours: Vec<Vec<Neighbour>>,
+    fetched: Vec<usize>,
+    cursors: Vec<usize>,
+    heap: BinaryHeap<Reverse<(i64, usize, usize, usize)>>,
+}
+
+impl<P: KdPoint> ClosestPairs<'_, P> {
+    /// Queues the next neighbour of point `i`, fetching twice as many as before when it runs out.
+    fn advance(&mut self, i: usize) {
+        let cursor = self.cursors[i];
+        // the point itself usually comes back as its own nearest neighbour, so a bigger fetch
+        // isn't guaranteed to add anything new
+        while cursor == self.neighbours[i].len() {
+            if self.fetched[i] == self.tree.len() {
+                return;
+            }
+            self.fetched[i] = (self.fetched[i] * 2).max(2).min(self.tree.len());
+            self.neighbours[i] = self
+                .tree
+                .k_nearest(self.tree.point(i), self.fetched[i])
+                .into_iter()
+                .filter(|(_, j)| *j != i)
+                .collect();
+        }
+
+        let (distance, j) = self.neighbours[i][cursor];
+        self.cursors[i] += 1;
+        self.heap.push(Reverse((distance, i.min(j), i.max(j), i)));
+    }
+}
+
+impl<P: KdPoint> Iterator for ClosestPairs<'_, P> {
+    type Item = (i64, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // every pair gets queued once from each end; only the copy from the lower index counts
+        loop {
+            let Reverse((distance, i, j, from)) = self.heap.pop()?;
+            self.advance(from);
+            if from == i {
+                return Some((distance, i, j));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2025 day 8
+    fn sample() -> Vec<Point3<i64>> {
+        [
+            (162, 817, 812),
+            (57, 618, 57),
+            (906, 360, 560),
+            (592, 479, 940),
+            (352, 342, 300),
+            (466, 668, 158),
+            (542, 29, 236),
+            (431, 825, 988),
+            (739, 650, 466),
+            (52, 470, 668),
+            (216, 146, 977),
+            (819, 987, 18),
+            (117, 168, 530),
+            (805, 96, 715),
+            (346, 949, 466),
+            (970, 615, 88),
+            (941, 993, 340),
+            (862, 61, 35),
+            (984, 92, 344),
+            (425, 690, 689),
+        ]
+        .into_iter()
+        .map(|(x, y, z)| Point3::new(x, y, z))
+        .collect()
+    }
+
+    fn brute_force<P: KdPoint>(points: &[P], target: &P) -> Vec<Neighbour> {
+        let mut all: Vec<Neighbour> = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.distance_squared(target), i))
+            .collect();
+        all.sort_unstable();
+        all
+    }
+
+    #[test]
+    fn sample_pairs() {
+        let points = sample();
+        let tree = KdTree::new(points.clone());
+
+        let mut all_pairs: Vec<(i64, usize, usize)> = (0..points.len())
+            .flat_map(|i| (i + 1..points.len()).map(move |j| (i, j)))
+            .map(|(i, j)| (points[i].distance_squared(&points[j]), i, j))
+            .collect();
+        all_pairs.sort_unstable();
+
+        let pairs: Vec<_> = tree.closest_pairs().collect();
+        assert_eq!(all_pairs, pairs);
+        // 162,817,812 and 425,690,689 are the closest
+        assert_eq!((0, 19), (pairs[0].1, pairs[0].2));
+    }
+
+    #[test]
+    fn queries() {
+        let points = sample();
+        let tree = KdTree::new(points.clone());
+
+        for target in points
+            .iter()
+            .chain(&[Point3::new(500, 500, 500), Point3::new(0, 0, 0)])
+        {
+            let expected = brute_force(&points, target);
+            assert_eq!(Some(expected[0]), tree.nearest(target));
+            assert_eq!(expected[..5].to_vec(), tree.k_nearest(target, 5));
+            assert_eq!(expected, tree.k_nearest(target, 100));
+
+            let radius = 300 * 300;
+            let inside: Vec<_> = expected
+                .iter()
+                .copied()
+                .filter(|(d, _)| *d <= radius)
+                .collect();
+            assert_eq!(inside, tree.within(target, radius));
+        }
+    }
+
+    #[test]
+    fn flat() {
+        // lots of ties and repeated coordinates
+        let points: Vec<Point2<i64>> = (0..10)
+            .flat_map(|x| (0..10).map(move |y| Point2::new(x % 4, y)))
+            .collect();
+        let tree = KdTree::new(points.clone());
+
+        let target = Point2::new(2, 5);
+        assert_eq!(
+            brute_force(&points, &target)[..12].to_vec(),
+            tree.k_nearest(&target, 12)
+        );
+        assert_eq!(
+            (0..points.len() * (points.len() - 1) / 2).len(),
+            tree.closest_pairs().count()
+        );
+        let pairs: Vec<_> = tree.closest_pairs().take(50).collect();
+        assert!(pairs.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!((0, 0, 40), pairs[0]);
+
+        let same = KdTree::new(vec![Point2::new(1, 1); 4]);
+        let pairs: Vec<_> = same.closest_pairs().collect();
+        assert_eq!(
+            vec![
+                (0, 0, 1),
+                (0, 0, 2),
+                (0, 0, 3),
+                (0, 1, 2),
+                (0, 1, 3),
+                (0, 2, 3)
+            ],
+            pairs
+        );
+
+        let empty: KdTree<Point2<i64>> = KdTree::new(vec![]);
+        assert_eq!(None, empty.nearest(&target));
+        assert_eq!(0, empty.closest_pairs().count());
+    }
+}
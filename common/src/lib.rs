@@ -0,0 +1,32 @@
+//! Helpers shared between the yearly solution crates.
+
+// lets `#[derive(FromLine)]` refer to `::aoc_common` from inside this crate too
+extern crate self as aoc_common;
+
+pub use aoc_derive::FromLine;
+
+pub mod answer;
+pub mod automaton;
+pub mod bigint;
+pub mod bits;
+pub mod checked;
+pub mod compress;
+pub mod cycle;
+pub mod digits;
+pub mod graph;
+pub mod grid;
+pub mod hash;
+pub mod input;
+pub mod ints;
+pub mod iter;
+pub mod kdtree;
+pub mod memo;
+pub mod parallel;
+pub mod parse;
+pub mod point;
+pub mod polygon;
+pub mod prefix;
+pub mod search;
+pub mod simulation;
+pub mod text;
+pub mod vm;
@@ -0,0 +1,135 @@
+use std::hash::Hash;
+
+use crate::hash::FastMap;
+
+/// How often a [`Memo`] found its answer in the cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl Stats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / total as f64
+    }
+}
+
+/// A memoized recursive function.
+///
+/// The function is handed a `recurse` callback instead of calling itself, so every recursive call
+/// goes through the cache and nobody has to pass a `HashMap` around by hand. The cache is a
+/// [`FastMap`], since memoized keys are nearly always small integers or tuples of them.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_common::memo::Memo;
+///
+/// let mut fib = Memo::new(|fib, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+/// assert_eq!(12586269025, fib.get(50));
+/// ```
+pub struct Memo<K, V, F> {
+    cache: FastMap<K, V>,
+    stats: Stats,
+    f: F,
+}
+
+impl<K, V, F> Memo<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            cache: FastMap::default(),
+            stats: Stats::default(),
+            f,
+        }
+    }
+
+    pub fn get(&mut self, key: K) -> V {
+        Self::lookup(&mut self.cache, &mut self.stats, &self.f, key)
+    }
+
+    fn lookup(cache: &mut FastMap<K, V>, stats: &mut Stats, f: &F, key: K) -> V {
+        if let Some(value) = cache.get(&key) {
+            stats.hits += 1;
+            return value.clone();
+        }
+        stats.misses += 1;
+
+        let value = f(&mut |k| Self::lookup(cache, stats, f, k), key.clone());
+        cache.insert(key, value.clone());
+
+        value
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Forgets every cached value and resets the stats.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.stats = Stats::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        let mut fib = Memo::new(|fib, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+
+        assert_eq!(55, fib.get(10));
+        // 0..=10 are each computed once, everything else is a repeat
+        assert_eq!(11, fib.len());
+        assert_eq!(
+            Stats {
+                hits: 8,
+                misses: 11
+            },
+            fib.stats()
+        );
+
+        assert_eq!(55, fib.get(10));
+        assert_eq!(9, fib.stats().hits);
+
+        fib.clear();
+        assert!(fib.is_empty());
+        assert_eq!(0.0, fib.stats().hit_rate());
+    }
+
+    #[test]
+    fn tuple_keys() {
+        // lattice paths through a grid, the classic two-argument recursion
+        let mut paths = Memo::new(|paths, (x, y): (u32, u32)| {
+            if x == 0 || y == 0 {
+                1_u64
+            } else {
+                paths((x - 1, y)) + paths((x, y - 1))
+            }
+        });
+
+        assert_eq!(6, paths.get((2, 2)));
+        assert_eq!(137846528820, paths.get((20, 20)));
+        assert_eq!(paths.len(), paths.stats().misses);
+        assert!(paths.stats().hits > 0);
+    }
+}
@@ -0,0 +1,214 @@
+//! Spreading independent work over a slice across threads, with nothing but std.
+//!
+//! The slice is cut into chunks that depend only on its length. Each thread starts on its own
+//! share of the chunks and steals from the others once it runs out. Results are put back together
+//! in chunk order, so a sum comes out the same on one thread or sixteen, floats included.
+//!
+//! ```
+//! use aoc_common::parallel::ParallelSlice;
+//!
+//! let numbers: Vec<u64> = (1..=1000).collect();
+//! assert_eq!(500500, numbers.par_sum(|n| *n));
+//! assert_eq!(500, numbers.par_filter_count(|n| n % 2 == 0));
+//! assert_eq!(vec![2, 4, 6], numbers[..3].par_map(|n| n * 2));
+//! ```
+
+use std::{collections::VecDeque, iter::Sum, num::NonZeroUsize, ops::Range, sync::Mutex, thread};
+
+/// Roughly how many chunks a slice gets cut into, so a thread that finishes early has something
+/// left to steal.
+const CHUNKS: usize = 256;
+
+/// How many threads to run work on. Threads only exist for the length of each call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pool {
+    threads: usize,
+}
+
+impl Default for Pool {
+    /// As many threads as the machine has cores.
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+}
+
+impl Pool {
+    pub fn new(threads: usize) -> Self {
+        assert!(threads > 0, "a pool needs at least one thread");
+        Self { threads }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// `f` of every item, in the same order as `items`.
+    pub fn map<T, R, F>(&self, items: &[T], f: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        self.chunks(items.len(), |range| {
+            items[range].iter().map(&f).collect::<Vec<R>>()
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// The sum of `f` over every item. Each chunk is summed in order, then the chunk totals are.
+    pub fn sum<T, S, F>(&self, items: &[T], f: F) -> S
+    where
+        T: Sync,
+        S: Send + Sum<S>,
+        F: Fn(&T) -> S + Sync,
+    {
+        self.chunks(items.len(), |range| items[range].iter().map(&f).sum::<S>())
+            .into_iter()
+            .sum()
+    }
+
+    /// How many items `f` is true for.
+    pub fn filter_count<T, F>(&self, items: &[T], f: F) -> usize
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.sum(items, |item| usize::from(f(item)))
+    }
+
+    /// Runs `job` over every chunk of `0..len`, and returns the results in chunk order.
+    fn chunks<R, J>(&self, len: usize, job: J) -> Vec<R>
+    where
+        R: Send,
+        J: Fn(Range<usize>) -> R + Sync,
+    {
+        let size = len.div_ceil(CHUNKS).max(1);
+        let ranges: Vec<Range<usize>> = (0..len)
+            .step_by(size)
+            .map(|start| start..(start + size).min(len))
+            .collect();
+        let threads = self.threads.min(ranges.len());
+        if threads <= 1 {
+            return ranges.into_iter().map(job).collect();
+        }
+
+        // every thread gets a run of neighbouring chunks to start with
+        let queues: Vec<Mutex<VecDeque<usize>>> = (0..threads)
+            .map(|t| {
+                let share = t * ranges.len() / threads..(t + 1) * ranges.len() / threads;
+                Mutex::new(share.collect())
+            })
+            .collect();
+        let results: Vec<Mutex<Option<R>>> = ranges.iter().map(|_| Mutex::new(None)).collect();
+
+        let work = |me: usize| {
+            // own work from the front, stolen work from the back of someone else's
+            let next = || {
+                let own = queues[me].lock().unwrap().pop_front();
+                own.or_else(|| {
+                    (1..threads)
+                        .map(|offset| (me + offset) % threads)
+                        .find_map(|other| queues[other].lock().unwrap().pop_back())
+                })
+            };
+            while let Some(chunk) = next() {
+                let result = job(ranges[chunk].clone());
+                *results[chunk].lock().unwrap() = Some(result);
+            }
+        };
+
+        thread::scope(|scope| {
+            for me in 1..threads {
+                scope.spawn(move || work(me));
+            }
+            work(0);
+        });
+
+        results
+            .into_iter()
+            .map(|result| result.into_inner().unwrap().expect("every chunk gets run"))
+            .collect()
+    }
+}
+
+/// Parallel versions of the usual slice loops, on the default [`Pool`].
+pub trait ParallelSlice<T: Sync> {
+    fn par_map<R: Send>(&self, f: impl Fn(&T) -> R + Sync) -> Vec<R>;
+
+    fn par_sum<S: Send + Sum<S>>(&self, f: impl Fn(&T) -> S + Sync) -> S;
+
+    fn par_filter_count(&self, f: impl Fn(&T) -> bool + Sync) -> usize;
+}
+
+impl<T: Sync> ParallelSlice<T> for [T] {
+    fn par_map<R: Send>(&self, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+        Pool::default().map(self, f)
+    }
+
+    fn par_sum<S: Send + Sum<S>>(&self, f: impl Fn(&T) -> S + Sync) -> S {
+        Pool::default().sum(self, f)
+    }
+
+    fn par_filter_count(&self, f: impl Fn(&T) -> bool + Sync) -> usize {
+        Pool::default().filter_count(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_on_any_pool() {
+        let items: Vec<u32> = (0..10_007).collect();
+        let expected: Vec<u64> = items.iter().map(|n| (*n as u64).pow(2)).collect();
+
+        for threads in [1, 2, 3, 8, 64] {
+            let pool = Pool::new(threads);
+            assert_eq!(expected, pool.map(&items, |n| (*n as u64).pow(2)));
+            assert_eq!(
+                expected.iter().sum::<u64>(),
+                pool.sum(&items, |n| (*n as u64).pow(2))
+            );
+            assert_eq!(1430, pool.filter_count(&items, |n| n % 7 == 0));
+        }
+
+        // floats don't add up the same in every order, so this only holds if the order is fixed
+        let floats: Vec<f64> = (1..5000).map(|n| 1.0 / n as f64).collect();
+        let one = Pool::new(1).sum(&floats, |x| *x);
+        let many = Pool::new(7).sum(&floats, |x| *x);
+        assert_eq!(one.to_bits(), many.to_bits());
+    }
+
+    #[test]
+    fn small() {
+        let empty: [u8; 0] = [];
+        assert!(Pool::new(4).map(&empty, |n| *n).is_empty());
+        assert_eq!(0, Pool::new(4).sum(&empty, |n| *n as u32));
+        assert_eq!(vec![6], [3].par_map(|n| n * 2));
+        assert!(Pool::default().threads() >= 1);
+    }
+
+    #[test]
+    fn uneven_work() {
+        // the slow items are all at the start, so the other threads have to steal
+        let items: Vec<u64> = (0..512).collect();
+        let busy = |n: &u64| {
+            if *n < 16 {
+                (0..200_000).fold(*n, |acc, i| acc.wrapping_mul(31).wrapping_add(i)) % 2
+            } else {
+                n % 2
+            }
+        };
+        let expected: u64 = items.iter().map(busy).sum();
+        assert_eq!(expected, Pool::new(4).sum(&items, busy));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one thread")]
+    fn no_threads() {
+        Pool::new(0);
+    }
+}
@@ -0,0 +1,507 @@
+//! Small parser combinators for puzzle input lines.
+//!
+//! Literal `&str`s and `char`s are parsers, and so are tuples of parsers, which run in sequence:
+//!
+//! ```
+//! use aoc_common::parse::{Parser, signed};
+//!
+//! let robot = ("p=", signed::<i32>(), ",", signed(), " v=", signed(), ",", signed())
+//!     .map(|(_, x, _, y, _, vx, _, vy)| ((x, y), (vx, vy)));
+//! assert_eq!(Ok(((0, 4), (3, -3))), robot.parse("p=0,4 v=3,-3"));
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+/// A parse that stopped partway, holding the input that was left when it did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure<'a> {
+    pub rest: &'a str,
+    pub expected: String,
+}
+
+pub type PResult<'a, T> = Result<(T, &'a str), Failure<'a>>;
+
+/// A failed parse of a whole input. `position` is the byte offset where things went wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub position: usize,
+    pub expected: String,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected {} at position {}",
+            self.expected, self.position
+        )
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Failure<'_> {
+    /// Turns this into an [`Error`], given the whole input that parsing started from.
+    pub fn locate(self, input: &str) -> Error {
+        Error {
+            position: input.len() - self.rest.len(),
+            expected: self.expected,
+        }
+    }
+}
+
+fn fail<'a, T>(rest: &'a str, expected: impl Into<String>) -> PResult<'a, T> {
+    Err(Failure {
+        rest,
+        expected: expected.into(),
+    })
+}
+
+pub trait Parser<'a> {
+    type Output;
+
+    /// Parses from the start of `input`, handing back whatever wasn't consumed.
+    fn parse_partial(&self, input: &'a str) -> PResult<'a, Self::Output>;
+
+    /// Parses all of `input`. Anything left over is an error.
+    fn parse(&self, input: &'a str) -> Result<Self::Output, Error> {
+        let (value, rest) = self.parse_partial(input).map_err(|f| f.locate(input))?;
+        end().parse_partial(rest).map_err(|f| f.locate(input))?;
+        Ok(value)
+    }
+
+    fn map<U, F>(self, f: F) -> impl Parser<'a, Output = U>
+    where
+        Self: Sized,
+        F: Fn(Self::Output) -> U,
+    {
+        from_fn(move |input| {
+            let (value, rest) = self.parse_partial(input)?;
+            Ok((f(value), rest))
+        })
+    }
+
+    /// Runs `next` afterwards, keeping both outputs.
+    fn then<P: Parser<'a>>(self, next: P) -> impl Parser<'a, Output = (Self::Output, P::Output)>
+    where
+        Self: Sized,
+    {
+        from_fn(move |input| {
+            let (first, rest) = self.parse_partial(input)?;
+            let (second, rest) = next.parse_partial(rest)?;
+            Ok(((first, second), rest))
+        })
+    }
+
+    /// Runs `next` afterwards, throwing its output away.
+    fn skip<P: Parser<'a>>(self, next: P) -> impl Parser<'a, Output = Self::Output>
+    where
+        Self: Sized,
+    {
+        from_fn(move |input| {
+            let (value, rest) = self.parse_partial(input)?;
+            let (_, rest) = next.parse_partial(rest)?;
+            Ok((value, rest))
+        })
+    }
+
+    /// Tries `other` from the same spot if this parser fails. When both fail, the error from
+    /// whichever got further is kept.
+    fn or<P: Parser<'a, Output = Self::Output>>(
+        self,
+        other: P,
+    ) -> impl Parser<'a, Output = Self::Output>
+    where
+        Self: Sized,
+    {
+        from_fn(move |input| match self.parse_partial(input) {
+            Ok(parsed) => Ok(parsed),
+            Err(first) => match other.parse_partial(input) {
+                Ok(parsed) => Ok(parsed),
+                Err(second) if first.rest.len() < second.rest.len() => Err(first),
+                Err(second) if first.rest.len() > second.rest.len() => Err(second),
+                Err(second) => fail(
+                    first.rest,
+                    format!("{} or {}", first.expected, second.expected),
+                ),
+            },
+        })
+    }
+
+    fn opt(self) -> impl Parser<'a, Output = Option<Self::Output>>
+    where
+        Self: Sized,
+    {
+        from_fn(move |input| match self.parse_partial(input) {
+            Ok((value, rest)) => Ok((Some(value), rest)),
+            Err(_) => Ok((None, input)),
+        })
+    }
+
+    /// One or more of this parser, with `separator` between each.
+    fn sep_by<P: Parser<'a>>(self, separator: P) -> impl Parser<'a, Output = Vec<Self::Output>>
+    where
+        Self: Sized,
+    {
+        from_fn(move |input| {
+            let (first, mut rest) = self.parse_partial(input)?;
+            let mut values = vec![first];
+            while let Ok((_, after_separator)) = separator.parse_partial(rest) {
+                let Ok((value, after_value)) = self.parse_partial(after_separator) else {
+                    break;
+                };
+                values.push(value);
+                rest = after_value;
+            }
+            Ok((values, rest))
+        })
+    }
+
+    /// Allows whitespace on either side.
+    fn trim(self) -> impl Parser<'a, Output = Self::Output>
+    where
+        Self: Sized,
+    {
+        from_fn(move |input| {
+            let (_, rest) = ws().parse_partial(input)?;
+            let (value, rest) = self.parse_partial(rest)?;
+            let (_, rest) = ws().parse_partial(rest)?;
+            Ok((value, rest))
+        })
+    }
+}
+
+/// A parser made from a plain function.
+pub struct FromFn<F>(F);
+
+pub fn from_fn<'a, T, F>(f: F) -> FromFn<F>
+where
+    F: Fn(&'a str) -> PResult<'a, T>,
+{
+    FromFn(f)
+}
+
+impl<'a, T, F> Parser<'a> for FromFn<F>
+where
+    F: Fn(&'a str) -> PResult<'a, T>,
+{
+    type Output = T;
+
+    fn parse_partial(&self, input: &'a str) -> PResult<'a, T> {
+        (self.0)(input)
+    }
+}
+
+impl<'a> Parser<'a> for &str {
+    type Output = &'a str;
+
+    fn parse_partial(&self, input: &'a str) -> PResult<'a, &'a str> {
+        match input.strip_prefix(*self) {
+            Some(rest) => Ok((&input[..self.len()], rest)),
+            None => fail(input, format!("{self:?}")),
+        }
+    }
+}
+
+impl<'a> Parser<'a> for char {
+    type Output = char;
+
+    fn parse_partial(&self, input: &'a str) -> PResult<'a, char> {
+        match input.strip_prefix(*self) {
+            Some(rest) => Ok((*self, rest)),
+            None => fail(input, format!("{self:?}")),
+        }
+    }
+}
+
+macro_rules! tuple_parser {
+    ($($name:ident),+) => {
+        impl<'a, $($name: Parser<'a>),+> Parser<'a> for ($($name,)+) {
+            type Output = ($($name::Output,)+);
+
+            #[allow(non_snake_case)]
+            fn parse_partial(&self, input: &'a str) -> PResult<'a, Self::Output> {
+                let ($($name,)+) = self;
+                let rest = input;
+                $(let ($name, rest) = $name.parse_partial(rest)?;)+
+                Ok((($($name,)+), rest))
+            }
+        }
+    };
+}
+
+tuple_parser!(A, B);
+tuple_parser!(A, B, C);
+tuple_parser!(A, B, C, D);
+tuple_parser!(A, B, C, D, E);
+tuple_parser!(A, B, C, D, E, F);
+tuple_parser!(A, B, C, D, E, F, G);
+tuple_parser!(A, B, C, D, E, F, G, H);
+
+/// The longest prefix (possibly empty) whose chars all match `predicate`.
+pub fn take_while<'a, P: Fn(char) -> bool>(predicate: P) -> impl Parser<'a, Output = &'a str> {
+    from_fn(move |input: &'a str| {
+        let end = input.find(|c| !predicate(c)).unwrap_or(input.len());
+        Ok(input.split_at(end))
+    })
+}
+
+/// Zero or more whitespace characters.
+pub fn ws<'a>() -> impl Parser<'a, Output = &'a str> {
+    take_while(char::is_whitespace)
+}
+
+/// One or more whitespace characters.
+pub fn ws1<'a>() -> impl Parser<'a, Output = &'a str> {
+    from_fn(|input: &'a str| match ws().parse_partial(input)? {
+        ("", _) => fail(input, "whitespace"),
+        parsed => Ok(parsed),
+    })
+}
+
+fn number<'a, T: FromStr>(input: &'a str, digits_from: usize, kind: &str) -> PResult<'a, T> {
+    let end = input[digits_from..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(input.len(), |len| digits_from + len);
+    if end == digits_from {
+        return fail(input, kind);
+    }
+
+    let (digits, rest) = input.split_at(end);
+    match digits.parse() {
+        Ok(value) => Ok((value, rest)),
+        Err(_) => fail(
+            input,
+            format!("{kind} that fits in {}", std::any::type_name::<T>()),
+        ),
+    }
+}
+
+/// Digits with no sign, e.g. `94`.
+pub fn unsigned<'a, T: FromStr>() -> impl Parser<'a, Output = T> {
+    from_fn(|input: &'a str| number(input, 0, "unsigned integer"))
+}
+
+/// Digits with an optional `+` or `-` in front, e.g. `-3`.
+pub fn signed<'a, T: FromStr>() -> impl Parser<'a, Output = T> {
+    from_fn(|input: &'a str| {
+        let sign = usize::from(input.starts_with(['-', '+']));
+        number(input, sign, "integer")
+    })
+}
+
+/// Succeeds only when there is no input left.
+pub fn end<'a>() -> impl Parser<'a, Output = ()> {
+    from_fn(|input: &'a str| match input {
+        "" => Ok(((), input)),
+        _ => fail(input, "end of input"),
+    })
+}
+
+/// Everything up to `terminator` (or the end of input when there isn't one), parsed as a `T`.
+///
+/// When that doesn't parse, the longest start of it that does is used instead, so that whatever
+/// comes next gets to report the mistake. Only when no start of it parses does the error point at
+/// the field itself.
+pub fn field<'a, T: FromStr>(terminator: Option<&'static str>) -> impl Parser<'a, Output = T> {
+    from_fn(move |input: &'a str| {
+        let end = terminator
+            .and_then(|t| input.find(t))
+            .unwrap_or(input.len());
+        let (text, rest) = input.split_at(end);
+        if let Ok(value) = text.parse() {
+            return Ok((value, rest));
+        }
+        text.char_indices()
+            .rev()
+            .filter(|(i, _)| *i > 0)
+            .find_map(|(i, _)| Some((input[..i].parse().ok()?, &input[i..])))
+            .map_or_else(|| fail(input, std::any::type_name::<T>()), Ok)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        // 2024 day 13
+        let button =
+            ("Button A: X+", unsigned::<usize>(), ", Y+", unsigned()).map(|(_, x, _, y)| (x, y));
+        assert_eq!(Ok((94, 34)), button.parse("Button A: X+94, Y+34"));
+
+        // 2024 day 7
+        let equation =
+            (unsigned::<u64>(), ": ", unsigned::<u64>().sep_by(' ')).map(|(r, _, v)| (r, v));
+        assert_eq!(
+            Ok((3267, vec![81, 40, 27])),
+            equation.parse("3267: 81 40 27")
+        );
+
+        // 2024 day 5
+        let rule = (unsigned::<u16>(), '|', unsigned::<u16>()).map(|(l, _, r)| (l, r));
+        assert_eq!(Ok((47, 53)), rule.parse("47|53"));
+    }
+
+    #[test]
+    fn errors() {
+        let pair = (signed::<i8>(), ',', signed::<i8>());
+        assert_eq!(
+            Err(Error {
+                position: 2,
+                expected: "','".to_string()
+            }),
+            pair.parse("12;4")
+        );
+        assert_eq!(
+            Err(Error {
+                position: 3,
+                expected: "integer that fits in i8".to_string()
+            }),
+            pair.parse("12,-200")
+        );
+        assert_eq!(Err(3), pair.parse("1,2 ").map_err(|e| e.position));
+        assert_eq!(
+            "expected unsigned integer or integer at position 0",
+            unsigned::<u8>()
+                .or(signed())
+                .parse("x")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn combinators() {
+        let list = unsigned::<u32>().sep_by(',').trim();
+        assert_eq!(Ok(vec![1, 2, 3]), list.parse("  1,2,3 "));
+        // a trailing separator is left for whoever comes next
+        assert_eq!(Ok((vec![1], ",")), list.parse_partial("1,"));
+
+        let maybe_negative = '-'.opt().then(unsigned::<u32>());
+        assert_eq!(Ok((Some('-'), 5)), maybe_negative.parse("-5"));
+        assert_eq!(Ok((None, 5)), maybe_negative.parse("5"));
+
+        let words = take_while(char::is_alphabetic)
+            .skip(ws1())
+            .then(take_while(|_| true));
+        assert_eq!(Ok(("hello", "there")), words.parse("hello \t there"));
+        assert!(words.parse("hello").is_err());
+
+        let turn = 'L'
+            .map(|_| -1)
+            .or('R'.map(|_| 1))
+            .then(unsigned::<i32>())
+            .map(|(dir, amt)| dir * amt);
+        assert_eq!(Ok(-68), turn.parse("L68"));
+        assert_eq!(Ok(48), turn.parse("R48"));
+        assert_eq!(
+            Err(Error {
+                position: 0,
+                expected: "'L' or 'R'".to_string()
+            }),
+            turn.parse("X1")
+        );
+    }
+
+    #[derive(crate::FromLine, Debug, PartialEq)]
+    #[pattern("p={position.0},{position.1} v={velocity.0},{velocity.1}")]
+    struct Robot {
+        position: (usize, usize),
+        velocity: (i32, i32),
+    }
+
+    #[derive(crate::FromLine, Debug, PartialEq)]
+    #[pattern("{},{},{}")]
+    struct Position {
+        x: u64,
+        y: u64,
+        z: u64,
+    }
+
+    #[derive(crate::FromLine, Debug, PartialEq)]
+    #[pattern(
+        "Button A: X+{a.0}, Y+{a.1}\nButton B: X+{b.0}, Y+{b.1}\nPrize: X={prize.0}, Y={prize.1}"
+    )]
+    struct Machine {
+        a: (u64, u64),
+        b: (u64, u64),
+        prize: (u64, u64),
+    }
+
+    #[derive(crate::FromLine, Debug, PartialEq)]
+    enum Turn {
+        #[pattern("L{0}")]
+        Left(i32),
+        #[pattern("R{0}")]
+        Right(i32),
+    }
+
+    #[derive(crate::FromLine, Debug, PartialEq)]
+    enum Instruction {
+        #[pattern("do()")]
+        Do,
+        #[pattern("don't()")]
+        Dont,
+        #[pattern("mul({0},{1})")]
+        Mul(u32, u32),
+    }
+
+    #[derive(crate::FromLine, Debug, PartialEq)]
+    #[pattern("{{{0}}}")]
+    struct Braced(String);
+
+    #[test]
+    fn derived() {
+        assert_eq!(
+            Ok(Robot {
+                position: (0, 4),
+                velocity: (3, -3)
+            }),
+            "p=0,4 v=3,-3".parse()
+        );
+        assert_eq!(
+            Ok(Position {
+                x: 162,
+                y: 817,
+                z: 812
+            }),
+            "162,817,812".parse()
+        );
+        assert_eq!(
+            Ok(Machine {
+                a: (94, 34),
+                b: (22, 67),
+                prize: (8400, 5400)
+            }),
+            "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400".parse()
+        );
+        assert_eq!(Ok(Turn::Left(68)), "L68".parse());
+        assert_eq!(Ok(Turn::Right(48)), "R48".parse());
+        assert_eq!(Ok(Instruction::Dont), "don't()".parse());
+        assert_eq!(Ok(Instruction::Mul(11, 8)), "mul(11,8)".parse());
+        assert_eq!(Ok(Braced("hi".to_string())), "{hi}".parse());
+    }
+
+    #[test]
+    fn derived_errors() {
+        let error = |position, expected: &str| Error {
+            position,
+            expected: expected.to_string(),
+        };
+        assert_eq!(Err(error(8, "i32")), "p=0,4 v=x,-3".parse::<Robot>());
+        assert_eq!(Err(error(5, "\" v=\"")), "p=0,4 _v=3,-3".parse::<Robot>());
+        assert_eq!(Err(error(5, "\" v=\"")), "p=0,4x v=3,-3".parse::<Robot>());
+        assert_eq!(
+            Err(error(12, "end of input")),
+            "p=0,4 v=3,-3!".parse::<Robot>()
+        );
+        assert_eq!(Err(error(4, "u64")), "162,,812".parse::<Position>());
+        assert_eq!(Err(error(0, "\"L\" or \"R\"")), "U2".parse::<Turn>());
+        assert_eq!(
+            Err(error(4, "end of input")),
+            "do()do()".parse::<Instruction>()
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Simple polygons with integer vertices.
+//!
+//! Everything is exact: areas come doubled so they stay integers, and points that land on half
+//! coordinates (rectangle centres, midpoints) are tested in doubled coordinates internally.
+
+use crate::point::Point2;
+
+type Point = Point2<i64>;
+
+/// Where a point is relative to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+fn cross(o: Point, a: Point, b: Point) -> i64 {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+fn on_segment(p: Point, a: Point, b: Point) -> bool {
+    cross(a, b, p) == 0
+        && p.x >= a.x.min(b.x)
+        && p.x <= a.x.max(b.x)
+        && p.y >= a.y.min(b.y)
+        && p.y <= a.y.max(b.y)
+}
+
+/// A closed loop of vertices, in either winding order, that doesn't cross itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Self {
+        Self { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// Every edge as `(from, to)`, including the one that closes the loop.
+    pub fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    }
+
+    /// Twice the area, from the shoelace formula. Always an integer for integer vertices.
+    pub fn double_area(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<i64>()
+            .abs()
+    }
+
+    /// Whether every edge is horizontal or vertical.
+    pub fn is_rectilinear(&self) -> bool {
+        self.edges().all(|(a, b)| a.x == b.x || a.y == b.y)
+    }
+
+    /// How many lattice points lie on the edges.
+    pub fn boundary_points(&self) -> i64 {
+        self.edges().map(|(a, b)| gcd(b.x - a.x, b.y - a.y)).sum()
+    }
+
+    /// How many lattice points lie strictly inside, from Pick's theorem: `A = I + B/2 - 1`.
+    pub fn interior_points(&self) -> i64 {
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+
+    /// Every lattice point inside or on the edge, e.g. the tiles a dug-out loop covers.
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    pub fn locate(&self, point: Point) -> Location {
+        self.locate_doubled(Point::new(point.x * 2, point.y * 2))
+    }
+
+    /// Inside or on an edge.
+    pub fn contains(&self, point: Point) -> bool {
+        self.locate(point) != Location::Outside
+    }
+
+    /// [`Polygon::locate`] for a point given in doubled coordinates.
+    fn locate_doubled(&self, q: Point) -> Location {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (a, b) = (Point::new(a.x * 2, a.y * 2), Point::new(b.x * 2, b.y * 2));
+            if on_segment(q, a, b) {
+                return Location::Boundary;
+            }
+            // ray cast to the right, comparing with a cross product instead of dividing
+            if (a.y > q.y) != (b.y > q.y) && (cross(a, b, q) > 0) == (b.y > a.y) {
+                inside = !inside;
+            }
+        }
+
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    /// Whether the axis-aligned rectangle with opposite corners `a` and `b` lies entirely inside
+    /// the polygon or on its edges. Zero-width rectangles are treated as segments.
+    pub fn contains_rect(&self, a: Point, b: Point) -> bool {
+        let min = Point::new(a.x.min(b.x), a.y.min(b.y));
+        let max = Point::new(a.x.max(b.x), a.y.max(b.y));
+
+        if min.x == max.x || min.y == max.y {
+            return self.contains_segment(min, max);
+        }
+
+        // with no edge cutting through the open rectangle, it's all on one side of the boundary,
+        // so its centre decides
+        !self.edges().any(|edge| crosses_open_rect(edge, min, max))
+            && self.locate_doubled(min + max) == Location::Inside
+    }
+
+    /// An axis-aligned segment. It can only enter or leave the polygon at a vertex lying on it
+    /// or where an edge crosses it, so it's split at the vertices and each piece is checked at its
+    /// midpoint.
+    fn contains_segment(&self, min: Point, max: Point) -> bool {
+        let crossed = self.edges().any(|(a, b)| {
+            let straddles = |p: Point| cross(a, b, p);
+            // strictly opposite sides both ways means a proper crossing
+            straddles(min).signum() * straddles(max).signum() < 0
+                && cross(min, max, a).signum() * cross(min, max, b).signum() < 0
+        });
+        if crossed {
+            return false;
+        }
+
+        let mut stops: Vec<Point> = self
+            .vertices
+            .iter()
+            .copied()
+            .filter(|v| on_segment(*v, min, max))
+            .chain([min, max])
+            .collect();
+        stops.sort_unstable_by_key(|p| (p.x, p.y));
+        stops.dedup();
+
+        self.contains(min)
+            && self.contains(max)
+            && stops
+                .windows(2)
+                .all(|w| self.locate_doubled(w[0] + w[1]) != Location::Outside)
+    }
+}
+
+/// Whether the segment touches the inside of the open box `(min, max)`, by looking for a
+/// separating axis: one of the box's sides, or the segment's own line.
+fn crosses_open_rect((a, b): (Point, Point), min: Point, max: Point) -> bool {
+    if a.x.max(b.x) <= min.x
+        || a.x.min(b.x) >= max.x
+        || a.y.max(b.y) <= min.y
+        || a.y.min(b.y) >= max.y
+    {
+        return false;
+    }
+
+    let corners = [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+    let sides: Vec<i64> = corners.iter().map(|c| cross(a, b, *c)).collect();
+    !(sides.iter().all(|s| *s >= 0) || sides.iter().all(|s| *s <= 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(i64, i64)]) -> Polygon {
+        Polygon::new(points.iter().map(|(x, y)| Point::new(*x, *y)).collect())
+    }
+
+    /// 2025 day 9, as a loop through the red tiles.
+    fn sample() -> Polygon {
+        polygon(&[
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ])
+    }
+
+    #[test]
+    fn sample_tiles() {
+        let tiles = sample();
+        assert!(tiles.is_rectilinear());
+        assert_eq!(2 * 30, tiles.double_area());
+        assert_eq!(30, tiles.boundary_points());
+        assert_eq!(16, tiles.interior_points());
+        assert_eq!(46, tiles.lattice_points());
+
+        assert_eq!(Location::Boundary, tiles.locate(Point::new(7, 2)));
+        assert_eq!(Location::Inside, tiles.locate(Point::new(8, 4)));
+        assert_eq!(Location::Outside, tiles.locate(Point::new(3, 2)));
+        assert_eq!(Location::Outside, tiles.locate(Point::new(10, 8)));
+
+        // the largest rectangle in part 2, from 9,5 to 2,3
+        assert!(tiles.contains_rect(Point::new(9, 5), Point::new(2, 3)));
+        // and one that pokes out of the top left
+        assert!(!tiles.contains_rect(Point::new(2, 5), Point::new(11, 1)));
+        assert!(tiles.contains_rect(Point::new(7, 3), Point::new(11, 1)));
+        assert!(tiles.contains_rect(Point::new(9, 7), Point::new(9, 5)));
+        assert!(!tiles.contains_rect(Point::new(2, 3), Point::new(7, 1)));
+    }
+
+    #[test]
+    fn notch() {
+        // a U, whose notch has every corner on the boundary but is outside
+        let u = polygon(&[
+            (0, 0),
+            (3, 0),
+            (3, 3),
+            (2, 3),
+            (2, 1),
+            (1, 1),
+            (1, 3),
+            (0, 3),
+        ]);
+        assert!(!u.contains_rect(Point::new(1, 1), Point::new(2, 3)));
+        assert!(!u.contains_rect(Point::new(1, 3), Point::new(2, 3)));
+        assert!(u.contains_rect(Point::new(0, 0), Point::new(3, 1)));
+        assert!(u.contains_rect(Point::new(0, 1), Point::new(0, 3)));
+        assert!(!u.contains_rect(Point::new(0, 2), Point::new(3, 2)));
+        assert_eq!(2 * 7, u.double_area());
+    }
+
+    #[test]
+    fn general() {
+        // a triangle, where Pick's theorem has real work to do
+        let triangle = polygon(&[(0, 0), (4, 0), (0, 3)]);
+        assert!(!triangle.is_rectilinear());
+        assert_eq!(12, triangle.double_area());
+        assert_eq!(8, triangle.boundary_points());
+        assert_eq!(3, triangle.interior_points());
+
+        assert_eq!(Location::Boundary, triangle.locate(Point::new(2, 0)));
+        assert_eq!(Location::Inside, triangle.locate(Point::new(1, 1)));
+        assert_eq!(Location::Outside, triangle.locate(Point::new(3, 2)));
+
+        assert!(triangle.contains_rect(Point::new(0, 0), Point::new(2, 1)));
+        assert!(!triangle.contains_rect(Point::new(0, 0), Point::new(2, 2)));
+        assert!(!triangle.contains_rect(Point::new(1, 3), Point::new(3, 3)));
+    }
+}
@@ -0,0 +1,241 @@
+//! Prefix sums, for adding up any range of a list or rectangle of a grid in constant time, and
+//! difference arrays, for the opposite: adding to whole ranges cheaply and reading back at the end.
+
+use std::ops::Range;
+
+use crate::grid::Grid;
+
+/// Running totals of a list, so any range adds up in constant time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixSum {
+    sums: Vec<i64>,
+}
+
+impl PrefixSum {
+    pub fn new<T: Into<i64>>(values: impl IntoIterator<Item = T>) -> Self {
+        let mut sums = vec![0];
+        let mut total = 0;
+        for value in values {
+            total += value.into();
+            sums.push(total);
+        }
+        Self { sums }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sums.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The total over `range`.
+    pub fn sum(&self, range: Range<usize>) -> i64 {
+        assert!(
+            range.end <= self.len(),
+            "{range:?} is outside a list of {}",
+            self.len()
+        );
+        if range.is_empty() {
+            return 0;
+        }
+        self.sums[range.end] - self.sums[range.start]
+    }
+
+    pub fn total(&self) -> i64 {
+        self.sums[self.len()]
+    }
+}
+
+/// Adds a value to a whole range at once by only touching its two ends. Read everything back with
+/// [`DifferenceArray::values`] once all the updates are in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifferenceArray {
+    diffs: Vec<i64>,
+}
+
+impl DifferenceArray {
+    pub fn new(len: usize) -> Self {
+        Self {
+            diffs: vec![0; len + 1],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.diffs.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn add(&mut self, range: Range<usize>, value: i64) {
+        assert!(
+            range.end <= self.len(),
+            "{range:?} is outside a list of {}",
+            self.len()
+        );
+        if range.is_empty() {
+            return;
+        }
+        self.diffs[range.start] += value;
+        self.diffs[range.end] -= value;
+    }
+
+    pub fn values(&self) -> Vec<i64> {
+        self.diffs[..self.len()]
+            .iter()
+            .scan(0, |total, diff| {
+                *total += diff;
+                Some(*total)
+            })
+            .collect()
+    }
+}
+
+/// A summed-area table: sums of every rectangle that starts at the top left corner. Anything that
+/// converts into an `i64` works, `bool` included, which turns it into a count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixSum2D {
+    sums: Vec<i64>,
+    width: usize,
+    height: usize,
+}
+
+impl PrefixSum2D {
+    pub fn new<T: Copy + Into<i64>>(grid: &Grid<T>) -> Self {
+        let (width, height) = (grid.width(), grid.height());
+        let stride = width + 1;
+        let mut sums = vec![0; stride * (height + 1)];
+        for row in 0..height {
+            for col in 0..width {
+                sums[(row + 1) * stride + col + 1] = grid[(row, col)].into()
+                    + sums[row * stride + col + 1]
+                    + sums[(row + 1) * stride + col]
+                    - sums[row * stride + col];
+            }
+        }
+
+        Self {
+            sums,
+            width,
+            height,
+        }
+    }
+
+    /// From sparse counts keyed by `(row, col)`, like a map of how many things sit on each
+    /// cell. Keys can repeat; their counts add up.
+    pub fn from_counts(
+        width: usize,
+        height: usize,
+        counts: impl IntoIterator<Item = ((usize, usize), i64)>,
+    ) -> Self {
+        let mut cells = Grid::new(width, height, 0);
+        for ((row, col), count) in counts {
+            let cell = cells
+                .get_mut(row, col)
+                .unwrap_or_else(|| panic!("({row}, {col}) is outside a {width}x{height} grid"));
+            *cell += count;
+        }
+        Self::new(&cells)
+    }
+
+    /// Counts how many of `points` land in each cell.
+    pub fn from_points(
+        width: usize,
+        height: usize,
+        points: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Self {
+        Self::from_counts(width, height, points.into_iter().map(|p| (p, 1)))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The total over `rows` × `cols`.
+    pub fn sum(&self, rows: Range<usize>, cols: Range<usize>) -> i64 {
+        assert!(
+            rows.end <= self.height && cols.end <= self.width,
+            "{rows:?} x {cols:?} is outside a {}x{} grid",
+            self.width,
+            self.height
+        );
+        if rows.is_empty() || cols.is_empty() {
+            return 0;
+        }
+        let at = |row: usize, col: usize| self.sums[row * (self.width + 1) + col];
+        at(rows.end, cols.end) - at(rows.start, cols.end) - at(rows.end, cols.start)
+            + at(rows.start, cols.start)
+    }
+
+    /// Like [`PrefixSum2D::sum`], but with both corners included.
+    pub fn sum_inclusive(&self, (r1, c1): (usize, usize), (r2, c2): (usize, usize)) -> i64 {
+        self.sum(r1.min(r2)..r1.max(r2) + 1, c1.min(c2)..c1.max(c2) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        let grid = Grid::from_fn(7, 5, |row, col| ((row * 7 + col) % 5) as i64 - 2);
+        let sums = PrefixSum2D::new(&grid);
+
+        for r1 in 0..=5 {
+            for r2 in r1..=5 {
+                for c1 in 0..=7 {
+                    for c2 in c1..=7 {
+                        let naive: i64 = (r1..r2)
+                            .flat_map(|r| (c1..c2).map(move |c| (r, c)))
+                            .map(|p| grid[p])
+                            .sum();
+                        assert_eq!(naive, sums.sum(r1..r2, c1..c2), "{r1}..{r2} x {c1}..{c2}");
+                    }
+                }
+            }
+        }
+
+        let walls = Grid::from_lines(["#..", ".#.", "..#"], |b| b == b'#');
+        let counts = PrefixSum2D::new(&walls);
+        assert_eq!(3, counts.sum(0..3, 0..3));
+        assert_eq!(2, counts.sum_inclusive((2, 2), (1, 0)));
+        assert_eq!(0, counts.sum_inclusive((0, 1), (0, 2)));
+
+        let sparse = PrefixSum2D::from_points(3, 3, [(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(counts, sparse);
+        let stacked = PrefixSum2D::from_counts(3, 2, [((1, 2), 4), ((0, 0), 1), ((1, 2), -1)]);
+        assert_eq!(3, stacked.sum(1..2, 1..3));
+        assert_eq!(4, stacked.sum(0..2, 0..3));
+    }
+
+    #[test]
+    #[should_panic(expected = "(3, 0) is outside a 3x3 grid")]
+    fn outside_point() {
+        PrefixSum2D::from_points(3, 3, [(3, 0)]);
+    }
+
+    #[test]
+    fn one_dimension() {
+        let sums = PrefixSum::new([3, -1, 4, 1, -5, 9]);
+        assert_eq!(6, sums.len());
+        assert_eq!(11, sums.total());
+        assert_eq!(4, sums.sum(1..4));
+        assert_eq!(0, sums.sum(2..2));
+        assert!(PrefixSum::new(Vec::<i64>::new()).is_empty());
+
+        let mut diffs = DifferenceArray::new(6);
+        diffs.add(0..3, 2);
+        diffs.add(2..6, 5);
+        diffs.add(4..4, 100);
+        assert_eq!(vec![2, 2, 7, 5, 5, 5], diffs.values());
+        assert_eq!(26, PrefixSum::new(diffs.values()).total());
+    }
+}
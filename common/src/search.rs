@@ -0,0 +1,234 @@
+//! Word searches: finding words along straight lines, and small 2D templates turned every which
+//! way.
+
+use crate::grid::{Grid, Pos};
+
+/// A word found in a grid: where its first letter is and which way the rest of it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WordMatch {
+    pub start: (usize, usize),
+    pub direction: Pos,
+}
+
+/// Every place `word` reads along one of `directions`, such as [`crate::grid::SURROUNDING`].
+/// A word that reads the same both ways turns up once for each way.
+pub fn find_word<T: PartialEq>(grid: &Grid<T>, word: &[T], directions: &[Pos]) -> Vec<WordMatch> {
+    let Some(first) = word.first() else {
+        return vec![];
+    };
+
+    grid.iter()
+        .filter(|(_, cell)| *cell == first)
+        .flat_map(|(start, _)| {
+            directions
+                .iter()
+                .filter(move |direction| reads(grid, word, start, **direction))
+                .map(move |direction| WordMatch {
+                    start,
+                    direction: *direction,
+                })
+        })
+        .collect()
+}
+
+fn reads<T: PartialEq>(grid: &Grid<T>, word: &[T], start: (usize, usize), (dr, dc): Pos) -> bool {
+    word.iter().enumerate().all(|(i, letter)| {
+        let row = start.0 as isize + dr * i as isize;
+        let col = start.1 as isize + dc * i as isize;
+        usize::try_from(row)
+            .ok()
+            .zip(usize::try_from(col).ok())
+            .and_then(|(row, col)| grid.get(row, col))
+            == Some(letter)
+    })
+}
+
+/// How a template was turned to make a match: mirrored left to right first if `flipped`, then
+/// rotated `quarter_turns` times clockwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub quarter_turns: u8,
+    pub flipped: bool,
+}
+
+/// A small picture to look for, where `None` cells match anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template<T> {
+    cells: Grid<Option<T>>,
+}
+
+impl Template<u8> {
+    /// One cell per byte, with `wildcard` matching anything.
+    pub fn parse<'a, I>(lines: I, wildcard: u8) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        Self::new(Grid::from_lines(lines, |b| (b != wildcard).then_some(b)))
+    }
+}
+
+impl<T: Clone + PartialEq> Template<T> {
+    pub fn new(cells: Grid<Option<T>>) -> Self {
+        Self { cells }
+    }
+
+    pub fn cells(&self) -> &Grid<Option<T>> {
+        &self.cells
+    }
+
+    fn rotated(&self) -> Self {
+        let cells = &self.cells;
+        let height = cells.height();
+        Self::new(Grid::from_fn(height, cells.width(), |row, col| {
+            cells[(height - 1 - col, row)].clone()
+        }))
+    }
+
+    fn flipped(&self) -> Self {
+        let cells = &self.cells;
+        let width = cells.width();
+        Self::new(Grid::from_fn(width, cells.height(), |row, col| {
+            cells[(row, width - 1 - col)].clone()
+        }))
+    }
+
+    /// Every distinct way the template can be rotated and mirrored. Symmetric templates have
+    /// fewer than eight, so nothing gets matched twice.
+    pub fn orientations(&self) -> Vec<(Orientation, Self)> {
+        let mut all: Vec<(Orientation, Self)> = vec![];
+        for flipped in [false, true] {
+            let mut turned = if flipped {
+                self.flipped()
+            } else {
+                self.clone()
+            };
+            for quarter_turns in 0..4 {
+                if !all.iter().any(|(_, seen)| *seen == turned) {
+                    let orientation = Orientation {
+                        quarter_turns,
+                        flipped,
+                    };
+                    all.push((orientation, turned.clone()));
+                }
+                turned = turned.rotated();
+            }
+        }
+        all
+    }
+
+    /// Whether the template, exactly as it is, fits with its top left corner at `top_left`.
+    pub fn matches_at(&self, grid: &Grid<T>, top_left: (usize, usize)) -> bool {
+        self.cells.iter().all(|((row, col), wanted)| match wanted {
+            None => top_left.0 + row < grid.height() && top_left.1 + col < grid.width(),
+            Some(wanted) => grid.get(top_left.0 + row, top_left.1 + col) == Some(wanted),
+        })
+    }
+}
+
+/// Where a template turned up, and which way round it was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TemplateMatch {
+    pub top_left: (usize, usize),
+    pub orientation: Orientation,
+}
+
+/// Every placement of `template` in any orientation.
+pub fn find_template<T: Clone + PartialEq>(
+    grid: &Grid<T>,
+    template: &Template<T>,
+) -> Vec<TemplateMatch> {
+    let mut found = vec![];
+    for (orientation, turned) in template.orientations() {
+        let (width, height) = (turned.cells.width(), turned.cells.height());
+        if width > grid.width() || height > grid.height() {
+            continue;
+        }
+        for row in 0..=grid.height() - height {
+            for col in 0..=grid.width() - width {
+                if turned.matches_at(grid, (row, col)) {
+                    found.push(TemplateMatch {
+                        top_left: (row, col),
+                        orientation,
+                    });
+                }
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{ORTHOGONAL, SURROUNDING};
+
+    /// 2024 day 4.
+    fn sample() -> Grid<u8> {
+        Grid::from_lines(
+            [
+                "MMMSXXMASM",
+                "MSAMXMSMSA",
+                "AMXSXMAAMM",
+                "MSAMASMSMX",
+                "XMASAMXAMM",
+                "XXAMMXXAMA",
+                "SMSMSASXSS",
+                "SAXAMASAAA",
+                "MAMMMXMMMM",
+                "MXMXAXMASX",
+            ],
+            |b| b,
+        )
+    }
+
+    #[test]
+    fn words() {
+        let grid = sample();
+        let xmas = find_word(&grid, b"XMAS", &SURROUNDING);
+        assert_eq!(18, xmas.len());
+        assert!(xmas.contains(&WordMatch {
+            start: (0, 5),
+            direction: (0, 1)
+        }));
+        assert!(xmas.contains(&WordMatch {
+            start: (4, 6),
+            direction: (0, -1)
+        }));
+
+        assert_eq!(8, find_word(&grid, b"XMAS", &ORTHOGONAL).len());
+        assert!(find_word(&grid, b"", &SURROUNDING).is_empty());
+        // every XMAS is a SAMX read from the other end
+        assert_eq!(18, find_word(&grid, b"SAMX", &SURROUNDING).len());
+    }
+
+    #[test]
+    fn templates() {
+        let grid = sample();
+        let x_mas = Template::parse(["M.S", ".A.", "M.S"], b'.');
+        // mirroring it is the same as turning it, so there are only four ways round
+        assert_eq!(4, x_mas.orientations().len());
+        let found = find_template(&grid, &x_mas);
+        assert_eq!(9, found.len());
+        assert!(found.contains(&TemplateMatch {
+            top_left: (0, 1),
+            orientation: Orientation {
+                quarter_turns: 0,
+                flipped: false
+            }
+        }));
+
+        let l = Template::parse(["X.", "X.", "XX"], b'?');
+        assert_eq!(8, l.orientations().len());
+        let corner = Template::parse(["AB", "C."], b'.');
+        assert_eq!(
+            Template::parse(["CA", ".B"], b'.'),
+            corner.orientations()[1].1
+        );
+        assert_eq!(
+            Template::parse(["BA", ".C"], b'.'),
+            corner.orientations()[4].1
+        );
+        let too_big = Template::parse(["MMMMMMMMMMM"], b'.');
+        assert!(find_template(&grid, &too_big).is_empty());
+    }
+}
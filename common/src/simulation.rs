@@ -0,0 +1,272 @@
+//! Step-by-step simulations. Puzzle code says what one step does, and a [`Runner`] decides how
+//! long to keep going and what to record along the way.
+
+use std::hash::Hash;
+
+use crate::cycle::{Cycle, Detector};
+
+pub trait Simulation {
+    /// Enough to tell two points in the simulation apart, for snapshots, fixed points and
+    /// repeats. Only taken when one of those is asked for.
+    type State: Clone + Eq + Hash;
+
+    fn step(&mut self);
+
+    fn state(&self) -> Self::State;
+
+    /// Called after every step, for anything that wants to keep its own statistics. Does
+    /// nothing by default.
+    fn observe(&mut self, _step: usize) {}
+}
+
+/// Why a run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// It took as many steps as it was allowed.
+    Limit,
+    /// The predicate from [`Runner::until`] came true.
+    Predicate,
+    /// The last step didn't change anything.
+    FixedPoint,
+    /// It got back to a state it had already been in.
+    Repeat(Cycle),
+}
+
+#[derive(Debug, Clone)]
+pub struct Outcome<T> {
+    /// How many steps were taken, including the one that triggered the stop.
+    pub steps: usize,
+    pub stop: Stop,
+    /// `(step, state)` for every snapshot taken, starting from step 0.
+    pub snapshots: Vec<(usize, T)>,
+}
+
+type Predicate<'a, S> = Box<dyn FnMut(&S, usize) -> bool + 'a>;
+type Renderer<'a, S> = Box<dyn FnMut(&S, usize) + 'a>;
+
+/// Runs a [`Simulation`] until one of its stop conditions is met. At least one has to be set.
+/// They're checked after each step in the order: predicate, fixed point, repeat, limit.
+pub struct Runner<'a, S: Simulation> {
+    limit: Option<usize>,
+    until: Option<Predicate<'a, S>>,
+    fixed_point: bool,
+    repeats: bool,
+    snapshot_every: Option<usize>,
+    render_every: Option<(usize, Renderer<'a, S>)>,
+}
+
+impl<S: Simulation> Default for Runner<'_, S> {
+    fn default() -> Self {
+        Self {
+            limit: None,
+            until: None,
+            fixed_point: false,
+            repeats: false,
+            snapshot_every: None,
+            render_every: None,
+        }
+    }
+}
+
+impl<'a, S: Simulation> Runner<'a, S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop after `steps` steps.
+    pub fn limit(mut self, steps: usize) -> Self {
+        self.limit = Some(steps);
+        self
+    }
+
+    /// Stop once `done(simulation, step)` is true. It's only asked after a step, never about the
+    /// starting state.
+    pub fn until(mut self, done: impl FnMut(&S, usize) -> bool + 'a) -> Self {
+        self.until = Some(Box::new(done));
+        self
+    }
+
+    /// Stop once a step leaves the state as it was.
+    pub fn until_fixed_point(mut self) -> Self {
+        self.fixed_point = true;
+        self
+    }
+
+    /// Stop the first time a state comes back around. Every state is kept until then.
+    pub fn detect_repeats(mut self) -> Self {
+        self.repeats = true;
+        self
+    }
+
+    /// Keep the state every `interval` steps, starting with the initial one.
+    pub fn snapshot_every(mut self, interval: usize) -> Self {
+        assert!(interval > 0, "snapshots need an interval of at least 1");
+        self.snapshot_every = Some(interval);
+        self
+    }
+
+    /// Hand the simulation to `frame` every `interval` steps, starting with the initial one.
+    pub fn render_every(mut self, interval: usize, frame: impl FnMut(&S, usize) + 'a) -> Self {
+        assert!(interval > 0, "frames need an interval of at least 1");
+        self.render_every = Some((interval, Box::new(frame)));
+        self
+    }
+
+    pub fn run(mut self, simulation: &mut S) -> Outcome<S::State> {
+        assert!(
+            self.limit.is_some() || self.until.is_some() || self.fixed_point || self.repeats,
+            "nothing would ever stop this simulation"
+        );
+
+        let mut snapshots = vec![];
+        let mut detector = Detector::default();
+        let mut previous = None;
+        self.record(simulation, 0, &mut snapshots);
+        if self.repeats || self.fixed_point {
+            let state = simulation.state();
+            if self.repeats {
+                detector.visit(&state);
+            }
+            previous = Some(state);
+        }
+
+        let mut steps = 0;
+        let stop = loop {
+            if self.limit == Some(steps) {
+                break Stop::Limit;
+            }
+
+            simulation.step();
+            steps += 1;
+            simulation.observe(steps);
+            self.record(simulation, steps, &mut snapshots);
+
+            if let Some(done) = &mut self.until
+                && done(simulation, steps)
+            {
+                break Stop::Predicate;
+            }
+            if self.repeats || self.fixed_point {
+                let state = simulation.state();
+                if self.fixed_point && previous.as_ref() == Some(&state) {
+                    break Stop::FixedPoint;
+                }
+                if self.repeats
+                    && let Some(cycle) = detector.visit(&state)
+                {
+                    break Stop::Repeat(cycle);
+                }
+                previous = Some(state);
+            }
+        };
+
+        Outcome {
+            steps,
+            stop,
+            snapshots,
+        }
+    }
+
+    fn record(&mut self, simulation: &S, step: usize, snapshots: &mut Vec<(usize, S::State)>) {
+        if let Some(interval) = self.snapshot_every
+            && step.is_multiple_of(interval)
+        {
+            snapshots.push((step, simulation.state()));
+        }
+        if let Some((interval, frame)) = &mut self.render_every
+            && step.is_multiple_of(*interval)
+        {
+            frame(simulation, step);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts up by `by`, wrapping at `modulo`, or stopping at `modulo` when it doesn't wrap.
+    struct Counter {
+        value: u32,
+        by: u32,
+        modulo: u32,
+        wraps: bool,
+        observed: usize,
+    }
+
+    impl Counter {
+        fn new(by: u32, modulo: u32, wraps: bool) -> Self {
+            Self {
+                value: 0,
+                by,
+                modulo,
+                wraps,
+                observed: 0,
+            }
+        }
+    }
+
+    impl Simulation for Counter {
+        type State = u32;
+
+        fn step(&mut self) {
+            self.value = if self.wraps {
+                (self.value + self.by) % self.modulo
+            } else {
+                (self.value + self.by).min(self.modulo)
+            };
+        }
+
+        fn state(&self) -> u32 {
+            self.value
+        }
+
+        fn observe(&mut self, step: usize) {
+            self.observed = step;
+        }
+    }
+
+    #[test]
+    fn stops() {
+        let mut counter = Counter::new(3, 10, true);
+        let outcome = Runner::new().limit(4).snapshot_every(2).run(&mut counter);
+        assert_eq!((4, Stop::Limit), (outcome.steps, outcome.stop));
+        assert_eq!(vec![(0, 0), (2, 6), (4, 2)], outcome.snapshots);
+        assert_eq!(4, counter.observed);
+
+        let outcome = Runner::new()
+            .until(|c: &Counter, _| c.value == 1)
+            .run(&mut counter);
+        assert_eq!((3, Stop::Predicate), (outcome.steps, outcome.stop));
+
+        let mut counter = Counter::new(4, 10, true);
+        counter.value = 3;
+        let outcome = Runner::new().detect_repeats().run(&mut counter);
+        assert_eq!(
+            Stop::Repeat(Cycle {
+                start: 0,
+                period: 5
+            }),
+            outcome.stop
+        );
+
+        let mut counter = Counter::new(4, 10, false);
+        let mut frames = vec![];
+        let outcome = Runner::new()
+            .until_fixed_point()
+            .limit(100)
+            .render_every(1, |c: &Counter, step| {
+                frames.push(format!("{step}: {}", c.value))
+            })
+            .run(&mut counter);
+        assert_eq!((4, Stop::FixedPoint), (outcome.steps, outcome.stop));
+        assert_eq!(10, counter.value);
+        assert_eq!(vec!["0: 0", "1: 4", "2: 8", "3: 10", "4: 10"], frames);
+    }
+
+    #[test]
+    #[should_panic(expected = "nothing would ever stop")]
+    fn endless() {
+        Runner::new().run(&mut Counter::new(1, 2, true));
+    }
+}
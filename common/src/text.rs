@@ -0,0 +1,189 @@
+use std::ops::Range;
+
+/// A rectangular block of text, kept as one buffer of bytes. Shorter lines are padded on the
+/// right with spaces, so every row is `width` long.
+///
+/// Everything works byte by byte, so it's meant for ASCII puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    bytes: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Block {
+    pub fn new<'a, I>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let lines: Vec<&[u8]> = lines.into_iter().map(str::as_bytes).collect();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let height = lines.len();
+
+        let mut bytes = Vec::with_capacity(width * height);
+        for line in lines {
+            bytes.extend_from_slice(line);
+            bytes.resize(bytes.len() + width - line.len(), b' ');
+        }
+
+        Self {
+            bytes,
+            width,
+            height,
+        }
+    }
+
+    fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> u8) -> Self {
+        let bytes = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| f(row, col))
+            .collect();
+
+        Self {
+            bytes,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> u8 {
+        self.bytes[row * self.width + col]
+    }
+
+    pub fn row(&self, row: usize) -> &[u8] {
+        &self.bytes[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    /// The bytes of one column, top to bottom.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = u8> + '_ {
+        self.bytes
+            .iter()
+            .skip(col)
+            .step_by(self.width.max(1))
+            .copied()
+    }
+
+    /// Only the columns in `cols`, as a new block.
+    pub fn columns(&self, cols: Range<usize>) -> Self {
+        Self::from_fn(cols.len(), self.height, |row, col| {
+            self.get(row, cols.start + col)
+        })
+    }
+
+    /// Splits the columns into groups wherever a column is entirely whitespace. The separator
+    /// columns themselves aren't part of any group.
+    pub fn column_groups(&self) -> Vec<Range<usize>> {
+        let mut groups = vec![];
+        let mut start = None;
+        for col in 0..self.width {
+            let blank = self.column(col).all(|b| b.is_ascii_whitespace());
+            match (start, blank) {
+                (None, false) => start = Some(col),
+                (Some(first), true) => {
+                    groups.push(first..col);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(first) = start {
+            groups.push(first..self.width);
+        }
+
+        groups
+    }
+
+    /// Rows become columns: the first column read top to bottom is the new first row.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |row, col| self.get(col, row))
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |row, col| {
+            self.get(self.height - 1 - col, row)
+        })
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |row, col| {
+            self.get(col, self.width - 1 - row)
+        })
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Self::from_fn(self.width, self.height, |row, col| {
+            self.get(row, self.width - 1 - col)
+        })
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Self::from_fn(self.width, self.height, |row, col| {
+            self.get(self.height - 1 - row, col)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(block: &Block) -> Vec<&str> {
+        block
+            .rows()
+            .map(|row| std::str::from_utf8(row).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn sample() {
+        // 2025 day 6, read in columns
+        let block = Block::new(["123 328  51 64", " 45 64  387 23", "  6 98  215 314"]);
+        assert_eq!(15, block.width());
+        assert_eq!(b"123 328  51 64 ", block.row(0));
+
+        let groups = block.column_groups();
+        assert_eq!(vec![0..3, 4..7, 8..11, 12..15], groups);
+
+        let first = block.columns(groups[0].clone()).transpose();
+        assert_eq!(vec!["1  ", "24 ", "356"], rows(&first));
+        let last = block.columns(groups[3].clone()).transpose();
+        assert_eq!(vec!["623", "431", "  4"], rows(&last));
+    }
+
+    #[test]
+    fn orientations() {
+        let block = Block::new(["ab", "cd", "ef"]);
+        assert_eq!(vec!["ace", "bdf"], rows(&block.transpose()));
+        assert_eq!(vec!["eca", "fdb"], rows(&block.rotate_clockwise()));
+        assert_eq!(vec!["bdf", "ace"], rows(&block.rotate_counter_clockwise()));
+        assert_eq!(vec!["ba", "dc", "fe"], rows(&block.flip_horizontal()));
+        assert_eq!(vec!["ef", "cd", "ab"], rows(&block.flip_vertical()));
+
+        assert_eq!(block, block.transpose().transpose());
+        let spun = (0..4).fold(block.clone(), |b, _| b.rotate_clockwise());
+        assert_eq!(block, spun);
+        assert_eq!(b"bdf".to_vec(), block.column(1).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn empty() {
+        let block = Block::new([]);
+        assert_eq!((0, 0), (block.width(), block.height()));
+        assert!(block.column_groups().is_empty());
+        assert_eq!(0, block.transpose().height());
+    }
+}
@@ -0,0 +1,333 @@
+//! A small machine for puzzles where the input is a program.
+//!
+//! An [`InstructionSet`] says how to read one instruction and what running it does. Everything
+//! else is shared: [`Program::scan`] picks instructions out of text full of junk,
+//! and a [`Vm`] runs them against a [`Machine`] of registers and flags, optionally keeping a
+//! [`Step`] trace of what happened.
+
+use std::fmt::Debug;
+
+use crate::parse::{self, PResult, Parser};
+
+/// Registers and flags, plus where the machine is in the program.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Machine {
+    pub registers: Vec<i64>,
+    pub flags: Vec<bool>,
+    pub pc: usize,
+}
+
+impl Machine {
+    /// A machine with every register at 0 and every flag off.
+    pub fn new(registers: usize, flags: usize) -> Self {
+        Self {
+            registers: vec![0; registers],
+            flags: vec![false; flags],
+            pc: 0,
+        }
+    }
+}
+
+/// Where to go after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Next,
+    /// Moves the program counter by this many instructions. Landing outside the program halts.
+    Jump(isize),
+    Halt,
+}
+
+/// One language of instructions.
+pub trait InstructionSet {
+    type Instruction: Clone + Debug;
+
+    /// A fresh machine for programs in this language.
+    fn machine(&self) -> Machine;
+
+    /// Reads one instruction from the start of `text`.
+    fn parse<'a>(&self, text: &'a str) -> PResult<'a, Self::Instruction>;
+
+    /// Whether an instruction could start with `c`. [`Program::scan`] only tries to parse where
+    /// this is true, since a failed parse builds its error message. Anything goes by default.
+    fn could_start(&self, _c: char) -> bool {
+        true
+    }
+
+    fn execute(&self, instruction: &Self::Instruction, machine: &mut Machine) -> Flow;
+}
+
+/// The instructions found in some text, and the byte offset each started at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program<I> {
+    pub instructions: Vec<(usize, I)>,
+}
+
+impl<I> Program<I> {
+    /// Every instruction in `text`, skipping over anything that doesn't parse as one.
+    pub fn scan<S>(set: &S, text: &str) -> Self
+    where
+        S: InstructionSet<Instruction = I>,
+    {
+        let mut instructions = vec![];
+        let mut rest = text;
+        while let Some(start) = rest.find(|c| set.could_start(c)) {
+            rest = &rest[start..];
+            let c = rest.chars().next().unwrap();
+            match set.parse(rest) {
+                Ok((instruction, after)) if after.len() < rest.len() => {
+                    instructions.push((text.len() - rest.len(), instruction));
+                    rest = after;
+                }
+                _ => rest = &rest[c.len_utf8()..],
+            }
+        }
+        Self { instructions }
+    }
+
+    /// Every line of `text` as one instruction. Unlike [`Program::scan`], nothing gets skipped.
+    pub fn lines<S>(set: &S, text: &str) -> Result<Self, parse::Error>
+    where
+        S: InstructionSet<Instruction = I>,
+    {
+        let mut instructions = vec![];
+        let mut offset = 0;
+        for line in text.split_inclusive('\n') {
+            let trimmed = line.trim_end();
+            if !trimmed.is_empty() {
+                let instruction = parse::from_fn(|input| set.parse(input))
+                    .parse(trimmed)
+                    .map_err(|mut e| {
+                        e.position += offset;
+                        e
+                    })?;
+                instructions.push((offset, instruction));
+            }
+            offset += line.len();
+        }
+        Ok(Self { instructions })
+    }
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+}
+
+/// One executed instruction, and the machine straight after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step<I> {
+    pub offset: usize,
+    pub instruction: I,
+    pub machine: Machine,
+}
+
+/// Runs programs for one instruction set. The machine carries over from one program to the next,
+/// so input split over several lines can be run a line at a time.
+pub struct Vm<S: InstructionSet> {
+    set: S,
+    machine: Machine,
+    trace: Option<Vec<Step<S::Instruction>>>,
+    limit: Option<usize>,
+}
+
+impl<S: InstructionSet> Vm<S> {
+    pub fn new(set: S) -> Self {
+        Self {
+            machine: set.machine(),
+            set,
+            trace: None,
+            limit: None,
+        }
+    }
+
+    /// Keeps a [`Step`] for every instruction run from now on.
+    pub fn traced(mut self) -> Self {
+        self.trace = Some(vec![]);
+        self
+    }
+
+    /// Gives up on a program after this many instructions, for ones that might loop forever.
+    pub fn limit(mut self, steps: usize) -> Self {
+        self.limit = Some(steps);
+        self
+    }
+
+    pub fn set(&self) -> &S {
+        &self.set
+    }
+
+    pub fn machine(&self) -> &Machine {
+        &self.machine
+    }
+
+    pub fn machine_mut(&mut self) -> &mut Machine {
+        &mut self.machine
+    }
+
+    /// Everything run so far, or nothing if the vm isn't [`Vm::traced`].
+    pub fn trace(&self) -> &[Step<S::Instruction>] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// Back to a fresh machine, with an empty trace.
+    pub fn reset(&mut self) {
+        self.machine = self.set.machine();
+        if let Some(trace) = &mut self.trace {
+            trace.clear();
+        }
+    }
+
+    /// Runs `program` from its first instruction until it halts or runs off either end, and
+    /// returns how many instructions ran. Returns `None` if it hit the [`Vm::limit`] first.
+    pub fn run(&mut self, program: &Program<S::Instruction>) -> Option<usize> {
+        self.machine.pc = 0;
+        let mut steps = 0;
+        while let Some((offset, instruction)) = program.instructions.get(self.machine.pc) {
+            if self.limit.is_some_and(|limit| steps >= limit) {
+                return None;
+            }
+            steps += 1;
+
+            let flow = self.set.execute(instruction, &mut self.machine);
+            let next = match flow {
+                Flow::Next => self.machine.pc.checked_add(1),
+                Flow::Jump(by) => self.machine.pc.checked_add_signed(by),
+                Flow::Halt => None,
+            };
+            if let Some(trace) = &mut self.trace {
+                trace.push(Step {
+                    offset: *offset,
+                    instruction: instruction.clone(),
+                    machine: self.machine.clone(),
+                });
+            }
+            match next {
+                Some(pc) => self.machine.pc = pc,
+                None => break,
+            }
+        }
+        Some(steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::signed;
+
+    /// Registers `a` and `b`, and a flag for whether the last `dec` reached zero.
+    struct Counter;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Op {
+        Set(usize, i64),
+        Dec(usize),
+        Add(usize, usize),
+        JumpUnlessZero(isize),
+        Halt,
+    }
+
+    fn register<'a>() -> impl Parser<'a, Output = usize> {
+        "a".map(|_| 0).or("b".map(|_| 1))
+    }
+
+    impl InstructionSet for Counter {
+        type Instruction = Op;
+
+        fn machine(&self) -> Machine {
+            Machine::new(2, 1)
+        }
+
+        fn parse<'a>(&self, text: &'a str) -> PResult<'a, Op> {
+            ("set ", register(), " ", signed())
+                .map(|(_, r, _, v)| Op::Set(r, v))
+                .or(("dec ", register()).map(|(_, r)| Op::Dec(r)))
+                .or(("add ", register(), " ", register()).map(|(_, r, _, s)| Op::Add(r, s)))
+                .or(("jnz ", signed()).map(|(_, by)| Op::JumpUnlessZero(by)))
+                .or("halt".map(|_| Op::Halt))
+                .parse_partial(text)
+        }
+
+        fn could_start(&self, c: char) -> bool {
+            "sdajh".contains(c)
+        }
+
+        fn execute(&self, op: &Op, machine: &mut Machine) -> Flow {
+            match *op {
+                Op::Set(r, v) => machine.registers[r] = v,
+                Op::Dec(r) => {
+                    machine.registers[r] -= 1;
+                    machine.flags[0] = machine.registers[r] == 0;
+                }
+                Op::Add(r, s) => machine.registers[r] += machine.registers[s],
+                Op::JumpUnlessZero(by) if !machine.flags[0] => return Flow::Jump(by),
+                Op::JumpUnlessZero(_) => {}
+                Op::Halt => return Flow::Halt,
+            }
+            Flow::Next
+        }
+    }
+
+    #[test]
+    fn loops() {
+        // b = 3 * 4, by adding 4 three times
+        let text = "set a 3\nset b 0\nadd b c\n";
+        let program = Program::lines(&Counter, text);
+        assert_eq!(
+            Err(parse::Error {
+                position: 22,
+                expected: "\"a\" or \"b\"".to_string()
+            }),
+            program
+        );
+
+        let text = "set a 3\nset b 0\nset c 4\n";
+        assert_eq!(20, Program::lines(&Counter, text).unwrap_err().position);
+
+        let program = Program::scan(
+            &Counter,
+            "set a 3; set b 4; add b b?? dec a; jnz -2; halt; dec b",
+        );
+        assert_eq!(7, program.len());
+        assert_eq!((18, Op::Add(1, 1)), program.instructions[2]);
+
+        let mut vm = Vm::new(Counter).traced();
+        // two sets, add/dec/jnz three times over, then the halt stops it before the last dec
+        assert_eq!(Some(2 + 3 * 3 + 1), vm.run(&program));
+        assert_eq!(vec![0, 32], vm.machine().registers);
+        assert_eq!(5, vm.machine().pc);
+
+        let trace = vm.trace();
+        assert_eq!(12, trace.len());
+        assert_eq!(Op::JumpUnlessZero(-2), trace[4].instruction);
+        assert_eq!(vec![2, 8], trace[4].machine.registers);
+        assert_eq!(Op::Halt, trace[11].instruction);
+
+        // the machine carries over between runs until it's reset
+        let double = Program::scan(&Counter, "add b b");
+        assert_eq!(Some(1), vm.run(&double));
+        assert_eq!(64, vm.machine().registers[1]);
+        vm.reset();
+        assert_eq!(Machine::new(2, 1), *vm.machine());
+        assert!(vm.trace().is_empty());
+    }
+
+    #[test]
+    fn runaway() {
+        let forever = Program::scan(&Counter, "set a 0 dec a jnz -1");
+        let mut vm = Vm::new(Counter).limit(100);
+        assert_eq!(None, vm.run(&forever));
+        assert!(vm.trace().is_empty());
+
+        // jumping off the front halts too
+        let backwards = Program::scan(&Counter, "set a 2 jnz -5 dec a");
+        assert_eq!(Some(2), Vm::new(Counter).run(&backwards));
+
+        let nothing = Program::scan(&Counter, "no instructions in here ✓");
+        assert!(nothing.is_empty());
+        assert_eq!(Some(0), Vm::new(Counter).run(&nothing));
+    }
+}